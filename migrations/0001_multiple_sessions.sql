@@ -0,0 +1,17 @@
+-- users_sessions.user_id was UNIQUE so a second login failed, sqlite can't drop
+-- a constraint in place so the table gets rebuilt
+CREATE TABLE users_sessions_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    expiry TEXT NOT NULL,
+    device_label TEXT,
+    created_at TEXT,
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+INSERT INTO users_sessions_new (id, user_id, expiry)
+SELECT id, user_id, expiry FROM users_sessions;
+
+DROP TABLE users_sessions;
+
+ALTER TABLE users_sessions_new RENAME TO users_sessions;
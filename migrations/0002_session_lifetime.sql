@@ -0,0 +1,3 @@
+-- sessions remember how long they were granted for so sliding expiry can
+-- extend remember me sessions by their own lifetime
+ALTER TABLE users_sessions ADD COLUMN lifetime_seconds INTEGER;
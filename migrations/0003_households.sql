@@ -0,0 +1,11 @@
+-- everything that existed before households gets put in one shared household
+CREATE TABLE households (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+INSERT INTO households (id, name) VALUES ('default', 'Default household');
+
+ALTER TABLE users ADD COLUMN household_id TEXT NOT NULL DEFAULT 'default' REFERENCES households(id);
+ALTER TABLE games ADD COLUMN household_id TEXT NOT NULL DEFAULT 'default' REFERENCES households(id);
+ALTER TABLE movies ADD COLUMN household_id TEXT NOT NULL DEFAULT 'default' REFERENCES households(id);
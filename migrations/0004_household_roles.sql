@@ -0,0 +1,10 @@
+-- existing users keep full control of what they already had
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'Owner';
+
+CREATE TABLE household_invites (
+    code TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    expiry TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
@@ -0,0 +1,12 @@
+ALTER TABLE users ADD COLUMN max_esrb_rating TEXT;
+ALTER TABLE users ADD COLUMN max_mpaa_rating TEXT;
+
+CREATE TABLE rating_exceptions (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
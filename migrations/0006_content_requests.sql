@@ -0,0 +1,27 @@
+ALTER TABLE rating_exceptions ADD COLUMN one_time INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE content_requests (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE TABLE content_request_decisions (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    request_id TEXT NOT NULL,
+    decided_by TEXT NOT NULL,
+    decision TEXT NOT NULL,
+    note TEXT,
+    decided_at TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(request_id) REFERENCES content_requests(id),
+    FOREIGN KEY(decided_by) REFERENCES users(id)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE loans (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    borrower TEXT NOT NULL,
+    lent_on TEXT NOT NULL,
+    due_on TEXT,
+    returned_on TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
@@ -0,0 +1,10 @@
+CREATE TABLE contacts (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT,
+    notes TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
+
+ALTER TABLE loans ADD COLUMN contact_id TEXT REFERENCES contacts(id);
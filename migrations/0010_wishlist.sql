@@ -0,0 +1,16 @@
+CREATE TABLE wishlist_items (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    platform TEXT,
+    format TEXT,
+    release_date TEXT,
+    notes TEXT,
+    claimed_by TEXT,
+    media_id TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id),
+    FOREIGN KEY(claimed_by) REFERENCES users(id)
+);
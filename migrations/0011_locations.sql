@@ -0,0 +1,11 @@
+CREATE TABLE locations (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    parent_id TEXT,
+    name TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(parent_id) REFERENCES locations(id)
+);
+
+ALTER TABLE games ADD COLUMN location_id TEXT REFERENCES locations(id);
+ALTER TABLE movies ADD COLUMN location_id TEXT REFERENCES locations(id);
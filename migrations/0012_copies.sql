@@ -0,0 +1,29 @@
+-- every existing game and movie row becomes a title with a single copy, and the
+-- location it was assigned to moves onto that copy
+CREATE TABLE copies (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    owner_id TEXT,
+    condition TEXT,
+    location_id TEXT,
+    purchased_on TEXT,
+    purchase_price_cents INTEGER,
+    purchased_from TEXT,
+    notes TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(owner_id) REFERENCES users(id),
+    FOREIGN KEY(location_id) REFERENCES locations(id)
+);
+
+INSERT INTO copies (id, household_id, media_kind, media_id, location_id)
+SELECT lower(hex(randomblob(16))), household_id, 'Game', id, location_id FROM games;
+
+INSERT INTO copies (id, household_id, media_kind, media_id, location_id)
+SELECT lower(hex(randomblob(16))), household_id, 'Movie', id, location_id FROM movies;
+
+-- sqlite won't drop a column that references another table, so the old
+-- location columns are only cleared out
+UPDATE games SET location_id = NULL;
+UPDATE movies SET location_id = NULL;
@@ -0,0 +1,7 @@
+ALTER TABLE copies ADD COLUMN has_media INTEGER;
+ALTER TABLE copies ADD COLUMN has_box INTEGER;
+ALTER TABLE copies ADD COLUMN has_manual INTEGER;
+ALTER TABLE copies ADD COLUMN sealed INTEGER;
+ALTER TABLE copies ADD COLUMN media_condition TEXT;
+ALTER TABLE copies ADD COLUMN box_condition TEXT;
+ALTER TABLE copies ADD COLUMN manual_condition TEXT;
@@ -0,0 +1,15 @@
+ALTER TABLE copies ADD COLUMN video_standard TEXT;
+ALTER TABLE copies ADD COLUMN region_code TEXT;
+
+CREATE TABLE hardware (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    platform TEXT,
+    format TEXT,
+    video_standard TEXT,
+    region_code TEXT,
+    region_free INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
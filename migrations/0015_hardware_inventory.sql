@@ -0,0 +1,3 @@
+ALTER TABLE hardware ADD COLUMN count INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE hardware ADD COLUMN condition TEXT;
+ALTER TABLE hardware ADD COLUMN broken INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE hardware ADD COLUMN revision TEXT;
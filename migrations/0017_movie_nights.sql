@@ -0,0 +1,12 @@
+ALTER TABLE movies ADD COLUMN runtime_minutes INTEGER;
+
+CREATE TABLE watch_log (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    watched_on TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
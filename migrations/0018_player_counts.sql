@@ -0,0 +1,11 @@
+ALTER TABLE games ADD COLUMN min_players INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE games ADD COLUMN max_local_players INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE games ADD COLUMN couch_coop INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN online INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN max_online_players INTEGER;
+
+-- the old single count was how many could play on one console
+UPDATE games SET max_local_players = MAX(COALESCE(number_of_players, 1), 1);
+UPDATE games SET couch_coop = max_local_players > 1;
+
+ALTER TABLE games DROP COLUMN number_of_players;
@@ -0,0 +1,11 @@
+ALTER TABLE users ADD COLUMN max_game_age INTEGER;
+
+CREATE TABLE game_ratings (
+    game_id TEXT NOT NULL,
+    household_id TEXT NOT NULL,
+    system TEXT NOT NULL,
+    rating TEXT NOT NULL,
+    PRIMARY KEY(game_id, system),
+    FOREIGN KEY(game_id) REFERENCES games(id),
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
@@ -0,0 +1,4 @@
+ALTER TABLE users_sessions ADD COLUMN absolute_expiry TEXT;
+
+-- Existing sessions keep the expiry they have now as their cap
+UPDATE users_sessions SET absolute_expiry = expiry WHERE absolute_expiry IS NULL;
@@ -0,0 +1,12 @@
+ALTER TABLE loans ADD COLUMN copy_id TEXT REFERENCES copies(id);
+
+-- loans from before copies were lent out individually go onto the copy that was
+-- made for their title when copies were introduced
+UPDATE loans SET copy_id = (
+    SELECT copies.id FROM copies
+    WHERE copies.household_id = loans.household_id
+    AND copies.media_kind = loans.media_kind
+    AND copies.media_id = loans.media_id
+    ORDER BY copies.rowid
+    LIMIT 1
+);
@@ -0,0 +1,2 @@
+-- a copy can only be out with one borrower at a time
+CREATE UNIQUE INDEX loans_open_copy ON loans(copy_id) WHERE returned_on IS NULL;
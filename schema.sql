@@ -1,29 +1,220 @@
+CREATE TABLE households (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE locations (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    parent_id TEXT,
+    name TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(parent_id) REFERENCES locations(id)
+);
+
 CREATE TABLE games (
     id TEXT PRIMARY KEY,
     title TEXT,
     platform TEXT,
     rating TEXT,
-    number_of_players INTEGER
+    household_id TEXT NOT NULL,
+    -- left over from before copies, always NULL
+    location_id TEXT,
+    min_players INTEGER NOT NULL DEFAULT 1,
+    max_local_players INTEGER NOT NULL DEFAULT 1,
+    couch_coop INTEGER NOT NULL DEFAULT 0,
+    online INTEGER NOT NULL DEFAULT 0,
+    max_online_players INTEGER,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(location_id) REFERENCES locations(id)
 );
 
 CREATE TABLE movies (
     id TEXT PRIMARY KEY,
     title TEXT,
     format TEXT,
-    rating TEXT
+    rating TEXT,
+    household_id TEXT NOT NULL,
+    -- left over from before copies, always NULL
+    location_id TEXT,
+    runtime_minutes INTEGER,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(location_id) REFERENCES locations(id)
 );
 
 CREATE TABLE users (
     id TEXT PRIMARY KEY,
     username TEXT UNIQUE,
-    passHash TEXT NOT NULL
+    passHash TEXT NOT NULL,
+    household_id TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'Owner',
+    max_esrb_rating TEXT,
+    max_mpaa_rating TEXT,
+    max_game_age INTEGER,
+    calendar_token TEXT UNIQUE,
+    FOREIGN KEY(household_id) REFERENCES households(id)
 );
 
 CREATE TABLE users_sessions (
     id TEXT PRIMARY KEY,
-    user_id TEXT UNIQUE NOT NULL, 
+    user_id TEXT NOT NULL,
     expiry TEXT NOT NULL,
+    device_label TEXT,
+    created_at TEXT,
+    lifetime_seconds INTEGER,
+    absolute_expiry TEXT,
     FOREIGN KEY(user_id) REFERENCES users(id)
-)
+);
+
+CREATE TABLE household_invites (
+    code TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    expiry TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
+
+CREATE TABLE rating_exceptions (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    one_time INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE TABLE content_requests (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE TABLE content_request_decisions (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    request_id TEXT NOT NULL,
+    decided_by TEXT NOT NULL,
+    decision TEXT NOT NULL,
+    note TEXT,
+    decided_at TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(request_id) REFERENCES content_requests(id),
+    FOREIGN KEY(decided_by) REFERENCES users(id)
+);
+
+CREATE TABLE contacts (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT,
+    notes TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
+
+CREATE TABLE loans (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    borrower TEXT NOT NULL,
+    contact_id TEXT,
+    lent_on TEXT NOT NULL,
+    due_on TEXT,
+    returned_on TEXT,
+    copy_id TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(contact_id) REFERENCES contacts(id),
+    FOREIGN KEY(copy_id) REFERENCES copies(id)
+);
+
+CREATE UNIQUE INDEX loans_open_copy ON loans(copy_id) WHERE returned_on IS NULL;
 
+CREATE TABLE wishlist_items (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    platform TEXT,
+    format TEXT,
+    release_date TEXT,
+    notes TEXT,
+    claimed_by TEXT,
+    media_id TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id),
+    FOREIGN KEY(claimed_by) REFERENCES users(id)
+);
 
+CREATE TABLE copies (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    owner_id TEXT,
+    condition TEXT,
+    location_id TEXT,
+    purchased_on TEXT,
+    purchase_price_cents INTEGER,
+    purchased_from TEXT,
+    notes TEXT,
+    has_media INTEGER,
+    has_box INTEGER,
+    has_manual INTEGER,
+    sealed INTEGER,
+    media_condition TEXT,
+    box_condition TEXT,
+    manual_condition TEXT,
+    video_standard TEXT,
+    region_code TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(owner_id) REFERENCES users(id),
+    FOREIGN KEY(location_id) REFERENCES locations(id)
+);
+
+CREATE TABLE hardware (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    platform TEXT,
+    format TEXT,
+    video_standard TEXT,
+    region_code TEXT,
+    region_free INTEGER NOT NULL DEFAULT 0,
+    count INTEGER NOT NULL DEFAULT 1,
+    condition TEXT,
+    broken INTEGER NOT NULL DEFAULT 0,
+    revision TEXT,
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
+
+CREATE TABLE watch_log (
+    id TEXT PRIMARY KEY,
+    household_id TEXT NOT NULL,
+    media_kind TEXT NOT NULL,
+    media_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    watched_on TEXT NOT NULL,
+    FOREIGN KEY(household_id) REFERENCES households(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE TABLE game_ratings (
+    game_id TEXT NOT NULL,
+    household_id TEXT NOT NULL,
+    system TEXT NOT NULL,
+    rating TEXT NOT NULL,
+    PRIMARY KEY(game_id, system),
+    FOREIGN KEY(game_id) REFERENCES games(id),
+    FOREIGN KEY(household_id) REFERENCES households(id)
+);
@@ -0,0 +1,95 @@
+use chrono::{TimeDelta, Utc};
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::sync::OnceLock;
+
+static SESSION_CONFIG: OnceLock<SessionConfig> = OnceLock::new();
+
+pub struct SessionConfig {
+    pub lifetime: TimeDelta,
+    pub remember_me_lifetime: TimeDelta,
+    pub sliding: bool,
+    pub sliding_limit: TimeDelta,
+}
+
+fn invalid_setting(key: &str) -> Error {
+    return Error::new(
+        ErrorKind::InvalidInput,
+        format!("{key} is not a valid lifetime"),
+    );
+}
+
+fn read_lifetime(
+    key: &str,
+    default: i64,
+    to_delta: fn(i64) -> Option<TimeDelta>,
+) -> Result<TimeDelta, Error> {
+    let amount = match env::var(key) {
+        Ok(value) => value.trim().parse::<i64>().or(Err(invalid_setting(key)))?,
+        Err(_) => default,
+    };
+    if amount <= 0 {
+        return Err(invalid_setting(key));
+    }
+    // the lifetime also has to fit on top of the current time when expiries are computed
+    return to_delta(amount)
+        .filter(|lifetime| Utc::now().checked_add_signed(*lifetime).is_some())
+        .ok_or(invalid_setting(key));
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        return SessionConfig {
+            lifetime: TimeDelta::minutes(120),
+            remember_me_lifetime: TimeDelta::days(30),
+            sliding: false,
+            sliding_limit: TimeDelta::hours(12),
+        };
+    }
+}
+
+impl SessionConfig {
+    pub fn from_env() -> Result<Self, Error> {
+        let lifetime = read_lifetime("SESSION_LIFETIME_MINUTES", 120, TimeDelta::try_minutes)?;
+        let remember_me_lifetime =
+            read_lifetime("REMEMBER_ME_LIFETIME_DAYS", 30, TimeDelta::try_days)?;
+        let sliding = env::var("SESSION_SLIDING")
+            .map(|value| value == "true" || value == "1")
+            .unwrap_or(false);
+        let sliding_limit = read_lifetime("SESSION_SLIDING_LIMIT_HOURS", 12, TimeDelta::try_hours)?;
+        return Ok(SessionConfig {
+            lifetime,
+            remember_me_lifetime,
+            sliding,
+            sliding_limit,
+        });
+    }
+
+    // read once at startup so bad values stop the server instead of a request
+    pub fn init() -> Result<(), Error> {
+        let config = SessionConfig::from_env()?;
+        let _ = SESSION_CONFIG.set(config);
+        return Ok(());
+    }
+
+    pub fn get() -> &'static SessionConfig {
+        return SESSION_CONFIG.get_or_init(SessionConfig::default);
+    }
+
+    pub fn lifetime_for(&self, remember_me: bool) -> TimeDelta {
+        if remember_me {
+            return self.remember_me_lifetime;
+        }
+        return self.lifetime;
+    }
+
+    // How long a session can be kept alive in total, which is also the cookie's max age.
+    // Sliding renewal stretches ordinary sessions up to the sliding limit, remembered
+    // sessions still end once their own lifetime is up
+    pub fn absolute_lifetime_for(&self, remember_me: bool) -> TimeDelta {
+        if self.sliding && !remember_me {
+            return self.sliding_limit.max(self.lifetime);
+        }
+        return self.lifetime_for(remember_me);
+    }
+}
@@ -1,3 +1,15 @@
 pub mod auth_models;
+pub mod calendar;
+pub mod contacts;
+pub mod content_requests;
+pub mod copies;
 pub mod game;
+pub mod hardware;
+pub mod loans;
+pub mod locations;
+pub mod lookup;
 pub mod movie;
+pub mod parental_controls;
+pub mod plans;
+pub mod watch_log;
+pub mod wishlist;
@@ -1,7 +1,61 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Owner,
+    Editor,
+    Viewer,
+    Child,
+}
+
+impl Role {
+    pub fn from_string(role_string: &str) -> Option<Self> {
+        match role_string {
+            "Owner" => Some(Self::Owner),
+            "Editor" => Some(Self::Editor),
+            "Viewer" => Some(Self::Viewer),
+            "Child" => Some(Self::Child),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self) -> String {
+        match self {
+            Self::Owner => "Owner".to_string(),
+            Self::Editor => "Editor".to_string(),
+            Self::Viewer => "Viewer".to_string(),
+            Self::Child => "Child".to_string(),
+        }
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        return match self {
+            Self::Owner => true,
+            Self::Editor => !matches!(
+                permission,
+                Permission::DeleteAll | Permission::ManageMembers
+            ),
+            Self::Viewer | Self::Child => permission == Permission::View,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    View,
+    Create,
+    Update,
+    Delete,
+    DeleteAll,
+    ManageMembers,
+    ApproveRequests,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct User {
+    pub user_id: String,
+    pub household_id: String,
+    pub role: Role,
     pub username: String,
     pub user_session: String,
 }
@@ -9,5 +63,43 @@ pub struct User {
 #[derive(Serialize, Deserialize)]
 pub struct LoginRequest {
     pub username: String,
-    pub pass_hash: String,
+    #[serde(alias = "pass_hash")]
+    pub password: String,
+    #[serde(default)]
+    pub device_label: Option<String>,
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub device_label: Option<String>,
+    pub created_at: Option<String>,
+    pub expiry: String,
+    pub current: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HouseholdMember {
+    pub user_id: String,
+    pub username: String,
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RoleChange {
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HouseholdInvite {
+    pub code: String,
+    pub role: Role,
+    pub expiry: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct JoinRequest {
+    pub code: String,
 }
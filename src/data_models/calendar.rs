@@ -0,0 +1,84 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+const LINE_LIMIT: usize = 75;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CalendarToken {
+    pub token: String,
+    pub feed_path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+}
+
+impl CalendarEvent {
+    fn lines(&self, stamp: &str) -> Vec<String> {
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", escape_text(&self.uid)),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART;VALUE=DATE:{}", self.date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                self.date.succ_opt().unwrap_or(self.date).format("%Y%m%d")
+            ),
+            format!("SUMMARY:{}", escape_text(&self.summary)),
+        ];
+        if let Some(description) = &self.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+        return lines;
+    }
+}
+
+pub fn render_calendar(events: &[CalendarEvent]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Kellum Library//Library Calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Kellum Library".to_string(),
+    ];
+    for event in events {
+        lines.extend(event.lines(&stamp));
+    }
+    lines.push("END:VCALENDAR".to_string());
+    return lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<String>>()
+        .join("\r\n")
+        + "\r\n";
+}
+
+fn escape_text(text: &str) -> String {
+    return text
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n");
+}
+
+// RFC 5545 wants lines no longer than 75 octets, continued with a leading space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut current_length = 0;
+    for c in line.chars() {
+        if current_length + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            current_length = 1;
+        }
+        folded.push(c);
+        current_length += c.len_utf8();
+    }
+    return folded;
+}
@@ -0,0 +1,18 @@
+use crate::data_models::loans::Loan;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Contact {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LendingHistory {
+    pub contact: Contact,
+    pub holding: Vec<Loan>,
+    pub returned: Vec<Loan>,
+}
@@ -0,0 +1,71 @@
+use crate::data_models::lookup::MediaKind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RequestStatus {
+    Pending,
+    ApprovedOnce,
+    ApprovedPermanently,
+    Denied,
+}
+
+impl RequestStatus {
+    pub fn from_string(status_string: &str) -> Option<Self> {
+        match status_string {
+            "Pending" => Some(Self::Pending),
+            "ApprovedOnce" => Some(Self::ApprovedOnce),
+            "ApprovedPermanently" => Some(Self::ApprovedPermanently),
+            "Denied" => Some(Self::Denied),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self) -> String {
+        match self {
+            Self::Pending => "Pending".to_string(),
+            Self::ApprovedOnce => "ApprovedOnce".to_string(),
+            Self::ApprovedPermanently => "ApprovedPermanently".to_string(),
+            Self::Denied => "Denied".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewContentRequest {
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentRequest {
+    pub id: String,
+    pub user_id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    pub status: RequestStatus,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestFilter {
+    pub status: Option<RequestStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Decision {
+    #[serde(default)]
+    pub permanent: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestDecision {
+    pub id: String,
+    pub request_id: String,
+    pub decided_by: String,
+    pub decision: RequestStatus,
+    pub note: Option<String>,
+    pub decided_at: String,
+}
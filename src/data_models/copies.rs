@@ -0,0 +1,187 @@
+use crate::data_models::game::Game;
+use crate::data_models::hardware::VideoStandard;
+use crate::data_models::loans::Loan;
+use crate::data_models::lookup::MediaKind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Condition {
+    Poor,
+    Fair,
+    Good,
+    NearMint,
+    Mint,
+}
+
+impl Condition {
+    pub fn from_string(condition_string: &str) -> Option<Self> {
+        return match condition_string {
+            "Poor" => Some(Self::Poor),
+            "Fair" => Some(Self::Fair),
+            "Good" => Some(Self::Good),
+            "NearMint" => Some(Self::NearMint),
+            "Mint" => Some(Self::Mint),
+            _ => None,
+        };
+    }
+
+    pub fn string(&self) -> String {
+        return match self {
+            Self::Poor => "Poor".to_string(),
+            Self::Fair => "Fair".to_string(),
+            Self::Good => "Good".to_string(),
+            Self::NearMint => "NearMint".to_string(),
+            Self::Mint => "Mint".to_string(),
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Completeness {
+    Loose,
+    Partial,
+    CIB,
+    Sealed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Part {
+    Media,
+    Box,
+    Manual,
+}
+
+// What is actually on the shelf for a copy, media being the cartridge or disc
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Parts {
+    pub has_media: bool,
+    pub has_box: bool,
+    pub has_manual: bool,
+    #[serde(default)]
+    pub sealed: bool,
+    pub media_condition: Option<Condition>,
+    pub box_condition: Option<Condition>,
+    pub manual_condition: Option<Condition>,
+}
+
+impl Parts {
+    pub fn completeness(&self) -> Completeness {
+        if self.sealed {
+            return Completeness::Sealed;
+        }
+        return match (self.has_media, self.has_box, self.has_manual) {
+            (true, true, true) => Completeness::CIB,
+            (true, false, false) => Completeness::Loose,
+            _ => Completeness::Partial,
+        };
+    }
+
+    pub fn missing(&self) -> Vec<Part> {
+        if self.sealed {
+            return vec![];
+        }
+        let mut missing = vec![];
+        if !self.has_media {
+            missing.push(Part::Media);
+        }
+        if !self.has_box {
+            missing.push(Part::Box);
+        }
+        if !self.has_manual {
+            missing.push(Part::Manual);
+        }
+        return missing;
+    }
+}
+
+// The parts of a copy anyone in the household can edit after it is recorded
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CopyDetails {
+    pub owner_id: Option<String>,
+    pub condition: Option<Condition>,
+    pub location_id: Option<String>,
+    pub purchased_on: Option<String>,
+    pub purchase_price_cents: Option<i64>,
+    pub purchased_from: Option<String>,
+    pub notes: Option<String>,
+    pub parts: Option<Parts>,
+    pub video_standard: Option<VideoStandard>,
+    pub region_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewCopy {
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    #[serde(flatten)]
+    pub details: CopyDetails,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediaCopy {
+    pub id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    #[serde(flatten)]
+    pub details: CopyDetails,
+    pub location: Option<String>,
+    pub completeness: Option<Completeness>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub warning: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub loan: Option<Loan>,
+}
+
+// Query filters for listing and searching by what copies we hold,
+// retro only narrows games and leaves movies out entirely
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CopyFilter {
+    pub completeness: Option<Completeness>,
+    pub min_condition: Option<Condition>,
+    pub retro: Option<bool>,
+}
+
+impl CopyFilter {
+    pub fn filters_copies(&self) -> bool {
+        return self.completeness.is_some() || self.min_condition.is_some();
+    }
+
+    pub fn matches(&self, copy: &MediaCopy) -> bool {
+        let completeness_matches = match self.completeness {
+            Some(completeness) => copy.completeness == Some(completeness),
+            None => true,
+        };
+        let condition_matches = match self.min_condition {
+            Some(min_condition) => copy
+                .details
+                .condition
+                .map(|condition| condition >= min_condition)
+                .unwrap_or(false),
+            None => true,
+        };
+        return completeness_matches && condition_matches;
+    }
+}
+
+#[derive(Serialize)]
+pub struct IncompleteCopy {
+    pub game: Game,
+    pub copy: MediaCopy,
+    pub missing: Vec<Part>,
+}
+
+// What /new takes, the title itself along with the copy that is being added
+#[derive(Deserialize)]
+pub struct NewTitle<T> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(default)]
+    pub copy: CopyDetails,
+}
+
+#[derive(Serialize)]
+pub struct ItemDetails<T: Serialize> {
+    #[serde(flatten)]
+    pub item: T,
+    pub copies: Vec<MediaCopy>,
+}
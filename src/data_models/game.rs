@@ -1,6 +1,26 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use uuid::Uuid;
 
+// Host platform, the older platform it runs, and the model numbers that can do it.
+// No model numbers means every revision of the host can.
+const BACKWARD_COMPATIBILITY: &[(PlatformType, PlatformType, &[&str])] = &[
+    (PlatformType::Playstation2, PlatformType::Playstation1, &[]),
+    (PlatformType::Playstation3, PlatformType::Playstation1, &[]),
+    // only the launch models kept the PS2 hardware on board
+    (
+        PlatformType::Playstation3,
+        PlatformType::Playstation2,
+        &["CECHA", "CECHB", "CECHC", "CECHE"],
+    ),
+    (PlatformType::Playstation5, PlatformType::Playstation4, &[]),
+    // the Family Edition and the Wii Mini dropped the GameCube ports
+    (PlatformType::Wii, PlatformType::GameCube, &["RVL-001"]),
+    (PlatformType::WiiU, PlatformType::Wii, &[]),
+    (PlatformType::Nintendo3DS, PlatformType::NintendoDS, &[]),
+    (PlatformType::Switch2, PlatformType::Switch, &[]),
+];
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum PlatformType {
     Playstation1,
@@ -65,9 +85,71 @@ impl PlatformType {
         }
         .to_string();
     }
+
+    // Systems that refuse discs and cartridges from another region
+    pub fn is_region_locked(self) -> bool {
+        return matches!(
+            self,
+            PlatformType::NES
+                | PlatformType::SNES
+                | PlatformType::N64
+                | PlatformType::GameCube
+                | PlatformType::Wii
+                | PlatformType::WiiU
+                | PlatformType::Nintendo3DS
+                | PlatformType::Playstation1
+                | PlatformType::Playstation2
+        );
+    }
+
+    // None when this platform can't run games for the other one at all
+    fn compatible_revisions(self, guest: PlatformType) -> Option<&'static [&'static str]> {
+        if self == guest {
+            return Some(&[]);
+        }
+        return BACKWARD_COMPATIBILITY
+            .iter()
+            .find(|(host, older, _)| *host == self && *older == guest)
+            .map(|(_, _, revisions)| *revisions);
+    }
+
+    pub fn can_ever_run(self, guest: PlatformType) -> bool {
+        return self.compatible_revisions(guest).is_some();
+    }
+
+    // An unknown revision only counts when every revision of the host would do
+    pub fn runs(self, guest: PlatformType, revision: Option<&str>) -> bool {
+        return match self.compatible_revisions(guest) {
+            Some([]) => true,
+            Some(revisions) => revision
+                .map(|revision| {
+                    let revision = revision.trim().to_uppercase();
+                    revisions.iter().any(|model| revision.starts_with(model))
+                })
+                .unwrap_or(false),
+            None => false,
+        };
+    }
+
+    pub fn is_handheld(self) -> bool {
+        return matches!(self, PlatformType::NintendoDS | PlatformType::Nintendo3DS);
+    }
+
+    // Older systems where it matters whether the box and manual are still around
+    pub fn is_retro(self) -> bool {
+        return matches!(
+            self,
+            PlatformType::NES
+                | PlatformType::SNES
+                | PlatformType::N64
+                | PlatformType::GameCube
+                | PlatformType::Playstation1
+                | PlatformType::Playstation2
+        );
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum ESRBRating {
     Everyone,
     Everyone10,
@@ -98,6 +180,126 @@ impl ESRBRating {
         }
         .to_string();
     }
+
+    // Everyone is meant for all ages, it sits level with PEGI 3 rather than above it
+    pub fn minimum_age(self) -> u8 {
+        return match self {
+            ESRBRating::Everyone => 3,
+            ESRBRating::Everyone10 => 10,
+            ESRBRating::Teen => 13,
+            ESRBRating::Mature => 17,
+            ESRBRating::AdultOnly => 18,
+        };
+    }
+}
+
+// Rating boards other than the ESRB, which keeps its own field on the game
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingSystem {
+    PEGI,
+    USK,
+    CERO,
+    ACB,
+}
+
+impl RatingSystem {
+    pub fn from_string(system_string: &str) -> Option<Self> {
+        return match system_string {
+            "PEGI" => Some(Self::PEGI),
+            "USK" => Some(Self::USK),
+            "CERO" => Some(Self::CERO),
+            "ACB" => Some(Self::ACB),
+            _ => None,
+        };
+    }
+
+    pub fn string(&self) -> String {
+        return match self {
+            Self::PEGI => "PEGI".to_string(),
+            Self::USK => "USK".to_string(),
+            Self::CERO => "CERO".to_string(),
+            Self::ACB => "ACB".to_string(),
+        };
+    }
+
+    // The youngest age a label is meant for, advisory labels count the same as legal ones.
+    // None means the label isn't one this board hands out.
+    pub fn minimum_age(&self, rating: &str) -> Option<u8> {
+        return match (self, rating) {
+            (Self::PEGI, "3") => Some(3),
+            (Self::PEGI, "7") => Some(7),
+            (Self::PEGI, "12") => Some(12),
+            (Self::PEGI, "16") => Some(16),
+            (Self::PEGI, "18") => Some(18),
+            (Self::USK, "0") => Some(0),
+            (Self::USK, "6") => Some(6),
+            (Self::USK, "12") => Some(12),
+            (Self::USK, "16") => Some(16),
+            (Self::USK, "18") => Some(18),
+            (Self::CERO, "A") => Some(0),
+            (Self::CERO, "B") => Some(12),
+            (Self::CERO, "C") => Some(15),
+            (Self::CERO, "D") => Some(17),
+            (Self::CERO, "Z") => Some(18),
+            (Self::ACB, "G") => Some(0),
+            (Self::ACB, "PG") => Some(8),
+            (Self::ACB, "M") => Some(15),
+            (Self::ACB, "MA15+") => Some(15),
+            (Self::ACB, "R18+") => Some(18),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegionalRating {
+    pub system: RatingSystem,
+    pub rating: String,
+}
+
+impl RegionalRating {
+    pub fn minimum_age(&self) -> Option<u8> {
+        return self.system.minimum_age(&self.rating);
+    }
+}
+
+// How many people a game takes. Local players share one console, couch co-op meaning they
+// play on the same side, while online players each bring their own system.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerCounts {
+    pub min_players: u8,
+    pub max_local_players: u8,
+    #[serde(default)]
+    pub couch_coop: bool,
+    #[serde(default)]
+    pub online: bool,
+    pub max_online_players: Option<u8>,
+}
+
+impl PlayerCounts {
+    pub fn is_valid(&self) -> bool {
+        return self.min_players >= 1
+            && self.min_players <= self.max_local_players
+            && (self.online || self.max_online_players.is_none())
+            && self
+                .max_online_players
+                .map(|max_players| max_players >= self.min_players)
+                .unwrap_or(true);
+    }
+
+    pub fn supports_local(&self, players: u8) -> bool {
+        return players >= self.min_players && players <= self.max_local_players;
+    }
+
+    // an online game without a known cap is taken at its word
+    pub fn supports_online(&self, players: u8) -> bool {
+        return self.online
+            && players >= self.min_players
+            && self
+                .max_online_players
+                .map(|max_players| players <= max_players)
+                .unwrap_or(true);
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -105,20 +307,81 @@ pub struct Game {
     pub id: String,
     pub title: String,
     pub platform: PlatformType,
-    pub rating: ESRBRating,
-    pub number_of_players: u8,
+    pub rating: Option<ESRBRating>,
+    #[serde(default)]
+    pub regional_ratings: Vec<RegionalRating>,
+    #[serde(flatten)]
+    pub players: PlayerCounts,
 }
 
 impl Game {
-    pub fn new(title: String, platform: &str, rating: &str, number_of_players: u8) -> Option<Game> {
+    pub fn new(
+        title: String,
+        platform: &str,
+        rating: Option<&str>,
+        regional_ratings: Vec<RegionalRating>,
+        players: PlayerCounts,
+    ) -> Option<Game> {
         let platform = PlatformType::platform_from_string(platform)?;
-        let rating = ESRBRating::rating_from_string(rating)?;
+        let rating = match rating {
+            Some(rating) => Some(ESRBRating::rating_from_string(rating)?),
+            None => None,
+        };
         return Some(Game {
             id: Uuid::new_v4().to_string(),
             title,
             platform,
             rating,
-            number_of_players,
+            regional_ratings,
+            players,
         });
     }
+
+    // Every game needs a rating from at least one board, and at most one from each
+    pub fn has_valid_ratings(&self) -> bool {
+        let mut systems = HashSet::new();
+        return (self.rating.is_some() || !self.regional_ratings.is_empty())
+            && self.regional_ratings.iter().all(|regional| {
+                regional.minimum_age().is_some() && systems.insert(regional.system)
+            });
+    }
+
+    // Boards disagree about the same game, so the strictest one wins
+    pub fn minimum_age(&self) -> Option<u8> {
+        return self
+            .rating
+            .map(|rating| rating.minimum_age())
+            .into_iter()
+            .chain(
+                self.regional_ratings
+                    .iter()
+                    .filter_map(|regional| regional.minimum_age()),
+            )
+            .max();
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct PlayerFilter {
+    pub players: Option<u8>,
+    pub couch_coop: Option<bool>,
+    pub online: Option<bool>,
+}
+
+impl PlayerFilter {
+    pub fn matches(&self, game: &Game) -> bool {
+        let counts = &game.players;
+        return self
+            .players
+            .map(|players| counts.supports_local(players) || counts.supports_online(players))
+            .unwrap_or(true)
+            && self
+                .couch_coop
+                .map(|couch_coop| counts.couch_coop == couch_coop)
+                .unwrap_or(true)
+            && self
+                .online
+                .map(|online| counts.online == online)
+                .unwrap_or(true);
+    }
 }
@@ -0,0 +1,310 @@
+use crate::data_models::copies::{Condition, MediaCopy};
+use crate::data_models::game::{Game, PlatformType};
+use crate::data_models::movie::{MotionPictureFormat, Movie};
+use serde::{Deserialize, Serialize};
+
+const DVD_REGIONS: &str = "012345678";
+const BLURAY_REGIONS: &str = "ABC";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum VideoStandard {
+    NTSC,
+    PAL,
+    #[serde(rename = "NTSC-J")]
+    NTSCJ,
+}
+
+impl VideoStandard {
+    pub fn from_string(standard_string: &str) -> Option<Self> {
+        return match standard_string {
+            "NTSC" => Some(Self::NTSC),
+            "PAL" => Some(Self::PAL),
+            "NTSC-J" => Some(Self::NTSCJ),
+            _ => None,
+        };
+    }
+
+    pub fn string(&self) -> String {
+        return match self {
+            Self::NTSC => "NTSC".to_string(),
+            Self::PAL => "PAL".to_string(),
+            Self::NTSCJ => "NTSC-J".to_string(),
+        };
+    }
+
+    // tapes only care about the signal, Japanese NTSC is still NTSC to a VCR
+    fn same_signal(&self, other: &VideoStandard) -> bool {
+        return self == other || (*self != Self::PAL && *other != Self::PAL);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum HardwareKind {
+    Console,
+    Player,
+    Controller,
+}
+
+impl HardwareKind {
+    pub fn from_string(kind_string: &str) -> Option<Self> {
+        return match kind_string {
+            "Console" => Some(Self::Console),
+            "Player" => Some(Self::Player),
+            "Controller" => Some(Self::Controller),
+            _ => None,
+        };
+    }
+
+    pub fn string(&self) -> String {
+        return match self {
+            Self::Console => "Console".to_string(),
+            Self::Player => "Player".to_string(),
+            Self::Controller => "Controller".to_string(),
+        };
+    }
+}
+
+fn default_count() -> u32 {
+    return 1;
+}
+
+// Consoles and controllers set a platform, players set the best format they can read.
+// A player's region_code can hold both its DVD digit and its Blu-ray letter, like "2B".
+// Identical items can share one entry through count, broken ones stay listed but never play anything.
+// A console's revision is its model number, which decides what older games it can take.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hardware {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub kind: HardwareKind,
+    pub platform: Option<PlatformType>,
+    pub format: Option<MotionPictureFormat>,
+    pub video_standard: Option<VideoStandard>,
+    pub region_code: Option<String>,
+    #[serde(default)]
+    pub region_free: bool,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    pub condition: Option<Condition>,
+    #[serde(default)]
+    pub broken: bool,
+    pub revision: Option<String>,
+}
+
+impl Hardware {
+    pub fn is_valid(&self) -> bool {
+        let fits_kind = match self.kind {
+            HardwareKind::Console | HardwareKind::Controller => {
+                self.platform.is_some() && self.format.is_none()
+            }
+            HardwareKind::Player => self.format.is_some() && self.platform.is_none(),
+        };
+        let valid_region = self
+            .region_code
+            .as_ref()
+            .map(|code| {
+                code.chars()
+                    .all(|c| DVD_REGIONS.contains(c) || BLURAY_REGIONS.contains(c))
+            })
+            .unwrap_or(true);
+        return !self.name.trim().is_empty() && fits_kind && valid_region && self.count > 0;
+    }
+
+    pub fn runs_platform(&self, platform: PlatformType) -> bool {
+        return match self.platform {
+            Some(host) if self.kind == HardwareKind::Console && !self.broken => {
+                host.runs(platform, self.revision.as_deref())
+            }
+            _ => false,
+        };
+    }
+
+    pub fn can_play_game(&self, game: &Game, copy: &MediaCopy) -> bool {
+        if !self.runs_platform(game.platform) {
+            return false;
+        }
+        if self.region_free || !game.platform.is_region_locked() {
+            return true;
+        }
+        return match (&copy.details.video_standard, &self.video_standard) {
+            (Some(disc), Some(console)) => disc == console,
+            _ => true,
+        };
+    }
+
+    pub fn reads_format(&self, format: &MotionPictureFormat) -> bool {
+        return match &self.format {
+            Some(player_format) if self.kind == HardwareKind::Player && !self.broken => {
+                format.plays_on(player_format)
+            }
+            _ => false,
+        };
+    }
+
+    pub fn can_play_movie(&self, movie: &Movie, copy: &MediaCopy) -> bool {
+        let reads_format = self.reads_format(&movie.format);
+        if !reads_format || self.region_free {
+            return reads_format;
+        }
+        if movie.format == MotionPictureFormat::VHS {
+            return match (&copy.details.video_standard, &self.video_standard) {
+                (Some(tape), Some(player)) => tape.same_signal(player),
+                _ => true,
+            };
+        }
+        let regions = match movie.format {
+            MotionPictureFormat::DVD => DVD_REGIONS,
+            MotionPictureFormat::BluRay => BLURAY_REGIONS,
+            _ => return true,
+        };
+        let disc_code = copy.details.region_code.clone().unwrap_or_default();
+        let player_code: String = self
+            .region_code
+            .clone()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| regions.contains(*c))
+            .collect();
+        // region 0 discs play anywhere, and an unknown code on either side gets the benefit of the doubt
+        if disc_code.is_empty() || player_code.is_empty() || disc_code.contains('0') {
+            return true;
+        }
+        return disc_code.chars().any(|c| player_code.contains(c));
+    }
+}
+
+// A disc is region locked when some of the players it could go in would refuse it
+pub fn is_region_locked_game(game: &Game, copy: &MediaCopy) -> bool {
+    return game.platform.is_region_locked() && copy.details.video_standard.is_some();
+}
+
+pub fn is_region_locked_movie(movie: &Movie, copy: &MediaCopy) -> bool {
+    return match movie.format {
+        MotionPictureFormat::DVD => copy
+            .details
+            .region_code
+            .as_ref()
+            .map(|code| !code.contains('0'))
+            .unwrap_or(false),
+        MotionPictureFormat::BluRay => copy
+            .details
+            .region_code
+            .as_ref()
+            .map(|code| !BLURAY_REGIONS.chars().all(|c| code.contains(c)))
+            .unwrap_or(false),
+        MotionPictureFormat::VHS => copy.details.video_standard.is_some(),
+        MotionPictureFormat::UltraHD => false,
+    };
+}
+
+pub fn valid_region_code(format: &MotionPictureFormat, code: &str) -> bool {
+    return match format {
+        MotionPictureFormat::DVD => {
+            !code.is_empty() && code.chars().all(|c| DVD_REGIONS.contains(c))
+        }
+        MotionPictureFormat::BluRay => {
+            !code.is_empty() && code.chars().all(|c| BLURAY_REGIONS.contains(c))
+        }
+        _ => false,
+    };
+}
+
+#[derive(Serialize)]
+pub struct CompatibilityReport {
+    pub copy_id: String,
+    pub region_locked: bool,
+    // whether anything working takes the platform or format at all, whatever the region
+    pub has_hardware: bool,
+    pub playable: bool,
+    pub compatible_hardware: Vec<Hardware>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum HardwareGap {
+    NoWorkingConsole,
+    NotEnoughControllers { needed: u8, owned: u32 },
+}
+
+#[derive(Serialize)]
+pub struct HardwareGapReport {
+    pub game: Game,
+    pub gaps: Vec<HardwareGap>,
+}
+
+pub struct HardwareInventory {
+    hardware: Vec<Hardware>,
+}
+
+impl HardwareInventory {
+    pub fn new(hardware: Vec<Hardware>) -> Self {
+        return HardwareInventory { hardware };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.hardware.is_empty();
+    }
+
+    pub fn runs(&self, platform: PlatformType) -> bool {
+        return self
+            .hardware
+            .iter()
+            .any(|item| item.runs_platform(platform));
+    }
+
+    // Hardware made for any system that runs the game counts, so 3DS units cover
+    // DS games and Switch 2 controllers cover Switch games
+    fn working(&self, kind: HardwareKind, platform: PlatformType) -> u32 {
+        return self
+            .hardware
+            .iter()
+            .filter(|item| {
+                item.kind == kind
+                    && !item.broken
+                    && item
+                        .platform
+                        .map(|host| host.runs(platform, item.revision.as_deref()))
+                        .unwrap_or(false)
+            })
+            .map(|item| item.count)
+            .sum();
+    }
+
+    // Handhelds need one system per player, and a computer is never short of a keyboard
+    pub fn controllers_for(&self, platform: PlatformType) -> Option<u32> {
+        return match platform {
+            PlatformType::Computer => None,
+            _ if platform.is_handheld() => Some(self.working(HardwareKind::Console, platform)),
+            _ => Some(self.working(HardwareKind::Controller, platform)),
+        };
+    }
+
+    pub fn gaps_for(&self, game: &Game) -> Vec<HardwareGap> {
+        let mut gaps = vec![];
+        if !self.runs(game.platform) {
+            gaps.push(HardwareGap::NoWorkingConsole);
+        }
+        if let Some(owned) = self.controllers_for(game.platform) {
+            if owned < game.players.max_local_players as u32 {
+                gaps.push(HardwareGap::NotEnoughControllers {
+                    needed: game.players.max_local_players,
+                    owned,
+                });
+            }
+        }
+        return gaps;
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PlayableQuery {
+    pub platform: Option<PlatformType>,
+    pub hardware_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PlayableGame {
+    #[serde(flatten)]
+    pub game: Game,
+    pub backward_compatible: bool,
+}
@@ -0,0 +1,35 @@
+use crate::data_models::lookup::MediaKind;
+use serde::{Deserialize, Serialize};
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Loan {
+    pub id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    pub copy_id: Option<String>,
+    pub borrower: String,
+    pub contact_id: Option<String>,
+    pub lent_on: String,
+    pub due_on: Option<String>,
+    pub returned_on: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckoutRequest {
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    // left out to lend whichever copy of the title is still on the shelf
+    #[serde(default)]
+    pub copy_id: Option<String>,
+    pub borrower: Option<String>,
+    pub contact_id: Option<String>,
+    pub lent_on: Option<String>,
+    pub due_on: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CheckinRequest {
+    pub returned_on: Option<String>,
+}
@@ -0,0 +1,41 @@
+use crate::data_models::lookup::MediaKind;
+use serde::{Deserialize, Serialize};
+
+pub const PATH_SEPARATOR: &str = " / ";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Location {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub name: String,
+    // "House / Den / Shelf 2", filled in from the parents when read back
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewLocation {
+    pub name: String,
+    pub parent_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocationAssignment {
+    pub copy_id: String,
+    pub location_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StoredItem {
+    pub kind: MediaKind,
+    pub id: String,
+    pub copy_id: String,
+    pub title: String,
+    pub location_id: String,
+    pub location: String,
+}
+
+#[derive(Serialize)]
+pub struct LocationContents {
+    pub location: Location,
+    pub items: Vec<StoredItem>,
+}
@@ -0,0 +1,99 @@
+use crate::data_models::{
+    game::{Game, PlatformType},
+    movie::{MotionPictureFormat, Movie},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Game,
+    Movie,
+}
+
+impl MediaKind {
+    pub fn from_string(kind_string: &str) -> Option<Self> {
+        match kind_string {
+            "Game" => Some(Self::Game),
+            "Movie" => Some(Self::Movie),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self) -> String {
+        match self {
+            Self::Game => "Game".to_string(),
+            Self::Movie => "Movie".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LookupQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateOptions {
+    #[serde(default)]
+    pub allow_duplicate: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LookupCandidate {
+    pub kind: MediaKind,
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<PlatformType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<MotionPictureFormat>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<String>,
+    pub score: f32,
+}
+
+impl LookupCandidate {
+    pub fn from_game(game: Game, score: f32) -> Self {
+        return LookupCandidate {
+            kind: MediaKind::Game,
+            id: game.id,
+            title: game.title,
+            platform: Some(game.platform),
+            format: None,
+            locations: vec![],
+            score,
+        };
+    }
+
+    pub fn from_movie(movie: Movie, score: f32) -> Self {
+        return LookupCandidate {
+            kind: MediaKind::Movie,
+            id: movie.id,
+            title: movie.title,
+            platform: None,
+            format: Some(movie.format),
+            locations: vec![],
+            score,
+        };
+    }
+}
+
+#[derive(Serialize)]
+pub struct LookupResponse {
+    pub query: String,
+    pub likely_owned: bool,
+    pub candidates: Vec<LookupCandidate>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MergeRequest {
+    pub survivor_id: String,
+    pub duplicate_ids: Vec<String>,
+}
+
+impl MergeRequest {
+    pub fn is_valid(&self) -> bool {
+        return !self.duplicate_ids.is_empty() && !self.duplicate_ids.contains(&self.survivor_id);
+    }
+}
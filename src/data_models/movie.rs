@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum MPAARating {
     GeneralAudiences,
     ParentalGuidance,
@@ -31,6 +31,16 @@ impl MPAARating {
             Self::AdultsOnly => "AdultsOnly".to_string(),
         }
     }
+
+    pub fn minimum_age(&self) -> u8 {
+        return match self {
+            Self::GeneralAudiences => 0,
+            Self::ParentalGuidance => 8,
+            Self::ParentsStronglyCautioned => 13,
+            Self::Restricted => 17,
+            Self::AdultsOnly => 18,
+        };
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -60,6 +70,16 @@ impl MotionPictureFormat {
             Self::VHS => "VHS".to_string(),
         }
     }
+
+    // Blu-ray players read DVDs and 4K players read both, tapes need a VCR
+    pub fn plays_on(&self, player: &MotionPictureFormat) -> bool {
+        return match self {
+            Self::VHS => *player == Self::VHS,
+            Self::DVD => *player != Self::VHS,
+            Self::BluRay => *player == Self::BluRay || *player == Self::UltraHD,
+            Self::UltraHD => *player == Self::UltraHD,
+        };
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -68,10 +88,16 @@ pub struct Movie {
     pub title: String,
     pub format: MotionPictureFormat,
     pub rating: MPAARating,
+    pub runtime_minutes: Option<u32>,
 }
 
 impl Movie {
-    pub fn new(title: &str, format: &str, rating: &str) -> Option<Self> {
+    pub fn new(
+        title: &str,
+        format: &str,
+        rating: &str,
+        runtime_minutes: Option<u32>,
+    ) -> Option<Self> {
         let format = MotionPictureFormat::from_string(format)?;
         let rating = MPAARating::from_string(rating)?;
         return Some(Movie {
@@ -79,6 +105,7 @@ impl Movie {
             title: title.to_string(),
             format,
             rating,
+            runtime_minutes,
         });
     }
 }
@@ -0,0 +1,73 @@
+use crate::data_models::{
+    game::{ESRBRating, Game},
+    lookup::MediaKind,
+    movie::{MPAARating, Movie},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RatingLimits {
+    pub max_esrb_rating: Option<ESRBRating>,
+    pub max_mpaa_rating: Option<MPAARating>,
+    pub max_game_age: Option<u8>,
+}
+
+impl RatingLimits {
+    // An ESRB limit and an age limit together mean whichever is stricter
+    pub fn game_age_limit(&self) -> Option<u8> {
+        return self
+            .max_esrb_rating
+            .map(|rating| rating.minimum_age())
+            .into_iter()
+            .chain(self.max_game_age)
+            .min();
+    }
+
+    // The game age limit is left out on purpose, a movie limit is always set on its own
+    pub fn movie_age_limit(&self) -> Option<u8> {
+        return self.max_mpaa_rating.map(|rating| rating.minimum_age());
+    }
+}
+
+// Narrows searches to what suits a given age, whatever board rated the item
+#[derive(Deserialize, Default)]
+pub struct AgeFilter {
+    pub max_age: Option<u8>,
+}
+
+impl AgeFilter {
+    pub fn allows_game(&self, game: &Game) -> bool {
+        return match self.max_age {
+            Some(max_age) => game
+                .minimum_age()
+                .map(|age| age <= max_age)
+                .unwrap_or(false),
+            None => true,
+        };
+    }
+
+    pub fn allows_movie(&self, movie: &Movie) -> bool {
+        return self
+            .max_age
+            .map(|max_age| movie.rating.minimum_age() <= max_age)
+            .unwrap_or(true);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RatingExceptionRequest {
+    pub user_id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    #[serde(default)]
+    pub one_time: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RatingException {
+    pub id: String,
+    pub user_id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    pub one_time: bool,
+}
@@ -0,0 +1,227 @@
+use crate::data_models::game::{ESRBRating, Game, PlayerCounts};
+use crate::data_models::hardware::HardwareInventory;
+use crate::data_models::loans::DATE_FORMAT;
+use crate::data_models::movie::{MPAARating, Movie};
+use chrono::{Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// The random half of a v4 uuid is plenty for picking what to play tonight
+pub fn random_index(len: usize) -> usize {
+    return (Uuid::new_v4().as_u128() % len as u128) as usize;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum PlayMode {
+    Local,
+    Online,
+}
+
+#[derive(Deserialize)]
+pub struct GamePlanQuery {
+    pub players: u8,
+    pub max_rating: Option<ESRBRating>,
+    pub max_age: Option<u8>,
+    pub mode: Option<PlayMode>,
+    #[serde(default)]
+    pub pick: bool,
+}
+
+impl GamePlanQuery {
+    fn suits(&self, game: &Game) -> bool {
+        let age_limit = self
+            .max_rating
+            .map(|rating| rating.minimum_age())
+            .into_iter()
+            .chain(self.max_age)
+            .min();
+        return match age_limit {
+            Some(max_age) => game
+                .minimum_age()
+                .map(|age| age <= max_age)
+                .unwrap_or(false),
+            None => true,
+        };
+    }
+
+    fn wants(&self, mode: PlayMode) -> bool {
+        return self.mode.map(|wanted| wanted == mode).unwrap_or(true);
+    }
+}
+
+// games built for exactly this many players come first
+fn sort_by_fit(games: &mut [Game], players: u8, capacity: fn(&PlayerCounts) -> u8) {
+    games.sort_by(|left, right| {
+        capacity(&left.players)
+            .saturating_sub(players)
+            .cmp(&capacity(&right.players).saturating_sub(players))
+            .then_with(|| left.title.cmp(&right.title))
+    });
+}
+
+// Local games need a controller for everyone in the room, online games only need our console.
+// A household that tracks no hardware at all is assumed to have whatever it takes.
+#[derive(Serialize)]
+pub struct GamePlan {
+    pub players: u8,
+    pub local: Vec<Game>,
+    pub online: Vec<Game>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pick: Option<Game>,
+}
+
+impl GamePlan {
+    pub fn build(games: Vec<Game>, inventory: &HardwareInventory, query: &GamePlanQuery) -> Self {
+        let candidates: Vec<Game> = games
+            .into_iter()
+            .filter(|game| {
+                query.suits(game) && (inventory.is_empty() || inventory.runs(game.platform))
+            })
+            .collect();
+        let mut local = vec![];
+        if query.wants(PlayMode::Local) {
+            local = candidates
+                .iter()
+                .filter(|game| {
+                    game.players.supports_local(query.players)
+                        && (inventory.is_empty()
+                            || inventory
+                                .controllers_for(game.platform)
+                                .map(|owned| owned >= query.players as u32)
+                                .unwrap_or(true))
+                })
+                .cloned()
+                .collect();
+            sort_by_fit(&mut local, query.players, |counts| counts.max_local_players);
+        }
+        let mut online = vec![];
+        if query.wants(PlayMode::Online) && query.players > 1 {
+            online = candidates
+                .into_iter()
+                .filter(|game| game.players.supports_online(query.players))
+                .collect();
+            sort_by_fit(&mut online, query.players, |counts| {
+                counts.max_online_players.unwrap_or(u8::MAX)
+            });
+        }
+        let mut pick = None;
+        if query.pick {
+            let mut seen = HashSet::new();
+            let pool: Vec<&Game> = local
+                .iter()
+                .chain(online.iter())
+                .filter(|game| seen.insert(game.id.clone()))
+                .collect();
+            if !pool.is_empty() {
+                pick = Some(pool[random_index(pool.len())].clone());
+            }
+        }
+        return GamePlan {
+            players: query.players,
+            local,
+            online,
+            pick,
+        };
+    }
+}
+
+const DEFAULT_SHORTLIST_LENGTH: usize = 10;
+
+// attendees is a comma separated list of household usernames
+#[derive(Deserialize)]
+pub struct MoviePlanQuery {
+    pub attendees: Option<String>,
+    pub max_rating: Option<MPAARating>,
+    pub max_runtime: Option<u32>,
+    pub skip_watched_months: Option<u32>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub pick: bool,
+}
+
+impl MoviePlanQuery {
+    pub fn attendee_names(&self) -> Vec<String> {
+        return self
+            .attendees
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct PlannedMovie {
+    #[serde(flatten)]
+    pub movie: Movie,
+    pub last_watched: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MoviePlan {
+    pub rating_ceiling: Option<MPAARating>,
+    pub shortlist: Vec<PlannedMovie>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pick: Option<PlannedMovie>,
+}
+
+impl MoviePlan {
+    // Movies with no runtime on record are kept rather than guessed at.
+    // Never watched comes first, then whatever has gone longest without a viewing.
+    pub fn build(
+        movies: Vec<Movie>,
+        last_watched: &HashMap<String, String>,
+        group_ceiling: Option<MPAARating>,
+        query: &MoviePlanQuery,
+    ) -> Self {
+        let rating_ceiling = match (group_ceiling, query.max_rating) {
+            (Some(group), Some(asked)) if asked < group => Some(asked),
+            (Some(group), _) => Some(group),
+            (None, asked) => asked,
+        };
+        let cutoff = query.skip_watched_months.and_then(|months| {
+            Utc::now()
+                .date_naive()
+                .checked_sub_months(Months::new(months))
+                .map(|date| date.format(DATE_FORMAT).to_string())
+        });
+        let mut candidates: Vec<PlannedMovie> = movies
+            .into_iter()
+            .filter(|movie| {
+                rating_ceiling
+                    .map(|ceiling| movie.rating <= ceiling)
+                    .unwrap_or(true)
+                    && match (query.max_runtime, movie.runtime_minutes) {
+                        (Some(max_runtime), Some(runtime)) => runtime <= max_runtime,
+                        _ => true,
+                    }
+            })
+            .map(|movie| PlannedMovie {
+                last_watched: last_watched.get(&movie.id).cloned(),
+                movie,
+            })
+            .filter(|planned| match (&cutoff, &planned.last_watched) {
+                (Some(cutoff), Some(watched_on)) => watched_on < cutoff,
+                _ => true,
+            })
+            .collect();
+        candidates.sort_by(|left, right| {
+            left.last_watched
+                .cmp(&right.last_watched)
+                .then_with(|| left.movie.title.cmp(&right.movie.title))
+        });
+        let mut pick = None;
+        if query.pick && !candidates.is_empty() {
+            pick = Some(candidates[random_index(candidates.len())].clone());
+        }
+        candidates.truncate(query.limit.unwrap_or(DEFAULT_SHORTLIST_LENGTH));
+        return MoviePlan {
+            rating_ceiling,
+            shortlist: candidates,
+            pick,
+        };
+    }
+}
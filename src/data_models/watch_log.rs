@@ -0,0 +1,16 @@
+use crate::data_models::lookup::MediaKind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchLogEntry {
+    pub id: String,
+    pub media_kind: MediaKind,
+    pub media_id: String,
+    pub user_id: String,
+    pub watched_on: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WatchRequest {
+    pub watched_on: Option<String>,
+}
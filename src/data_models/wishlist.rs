@@ -0,0 +1,58 @@
+use crate::data_models::{game::PlatformType, lookup::MediaKind, movie::MotionPictureFormat};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewWishlistItem {
+    pub media_kind: MediaKind,
+    pub title: String,
+    pub platform: Option<PlatformType>,
+    pub format: Option<MotionPictureFormat>,
+    pub release_date: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl NewWishlistItem {
+    pub fn is_valid(&self) -> bool {
+        let variant_matches_kind = match self.media_kind {
+            MediaKind::Game => self.format.is_none(),
+            MediaKind::Movie => self.platform.is_none(),
+        };
+        return !self.title.trim().is_empty() && variant_matches_kind;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WishlistItem {
+    pub id: String,
+    pub user_id: String,
+    pub username: String,
+    pub media_kind: MediaKind,
+    pub title: String,
+    pub platform: Option<PlatformType>,
+    pub format: Option<MotionPictureFormat>,
+    pub release_date: Option<String>,
+    pub notes: Option<String>,
+    // the game or movie that fulfilled this wish once it was added to the library
+    pub media_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimed_by: Option<String>,
+}
+
+impl WishlistItem {
+    // the person who made the wish shouldn't be able to tell a gift is coming
+    pub fn hide_claim_from(mut self, user_id: &str) -> Self {
+        if self.user_id == user_id {
+            self.claimed = None;
+            self.claimed_by = None;
+        }
+        return self;
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WishlistQuery {
+    #[serde(default)]
+    pub include_resolved: bool,
+}
@@ -1,4 +1,16 @@
 pub mod authentication_services;
+pub mod calendar_database;
+pub mod contact_database;
+pub mod content_request_database;
+pub mod copy_database;
 pub mod database_utilities;
 pub mod game_database;
+pub mod hardware_database;
+pub mod household_database;
+pub mod loan_database;
+pub mod location_database;
 pub mod movie_database;
+pub mod parental_controls;
+pub mod title_matching;
+pub mod watch_log_database;
+pub mod wishlist_database;
@@ -1,69 +1,145 @@
-use crate::data_models::auth_models::{LoginRequest, User};
+use crate::config::SessionConfig;
+use crate::data_models::auth_models::{LoginRequest, Permission, Role, SessionInfo, User};
 use crate::database_services::database_utilities::get_connection;
 use crate::errors::auth_errors::AuthServiceError;
-use crate::service_endpoints::auth_servce::register_user;
-use actix_web::guard::Put;
-use std::usize;
+use actix_web::dev::ServiceRequest;
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use rusqlite::params;
+use subtle::ConstantTimeEq;
 use uuid::Uuid;
 
+pub const SESSION_KEY: &str = "session_id";
+
+fn hash_password(password: &str) -> Result<String, AuthServiceError> {
+    let salt = SaltString::generate(&mut OsRng);
+    return match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(_) => Err(AuthServiceError::FailedToRegister),
+    };
+}
+
+// Rows from before server side hashing hold whatever the client sent, those get
+// compared in constant time once and then replaced with a real hash
+fn verify_password(password: &str, stored: &str) -> (bool, bool) {
+    return match PasswordHash::new(stored) {
+        Ok(parsed) => (
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok(),
+            false,
+        ),
+        Err(_) => {
+            let matches: bool = password.as_bytes().ct_eq(stored.as_bytes()).into();
+            (matches, matches)
+        }
+    };
+}
+
 pub fn get_user_id(name: String, pass: String) -> Result<String, AuthServiceError> {
-    let conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
+    let conn = get_connection().or(Err(AuthServiceError::FailedToAuthenticate))?;
     let res = conn.query_row(
-        "SELECT id FROM users WHERE username == 1? && passHash == 2?",
-        [name, pass],
+        "SELECT id, passHash FROM users WHERE username = ?1",
+        [name],
         |row| {
-            let id = row.get::<usize, String>(0)?;
-            return Ok(id);
+            return Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?));
         },
     );
-    return match res {
-        Ok(id) => Ok(id),
-        Err(_) => Err(AuthServiceError::FailedToAuthenticate),
-    };
+    let (id, stored) = res.or(Err(AuthServiceError::FailedToAuthenticate))?;
+    let (is_valid, needs_rehash) = verify_password(&pass, &stored);
+    if !is_valid {
+        return Err(AuthServiceError::FailedToAuthenticate);
+    }
+    if needs_rehash {
+        let new_hash = hash_password(&pass).or(Err(AuthServiceError::FailedToAuthenticate))?;
+        conn.execute(
+            "UPDATE users SET passHash = ?1 WHERE id = ?2",
+            [new_hash, id.clone()],
+        )
+        .or(Err(AuthServiceError::FailedToAuthenticate))?;
+    }
+    return Ok(id);
 }
 
 pub async fn validate_user_session(session_id: String) -> Result<User, AuthServiceError> {
-    let conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
-    let res = conn.query_row("SELECT us.id, u.username, us.expiry FROM user_sessions as us INNER JOIN users as u on us.user_id = u.id WHERE us.id == 1?", 
+    let conn = get_connection().or(Err(AuthServiceError::InvalidSessionToken))?;
+    let res = conn.query_row(
+        "SELECT us.id, u.username, u.id, u.household_id, u.role, us.lifetime_seconds,
+                COALESCE(us.absolute_expiry, us.expiry)
+         FROM users_sessions AS us
+         INNER JOIN users AS u ON us.user_id = u.id
+         WHERE us.id = ?1 AND NOT is_expired(us.expiry)",
         [session_id],
         |row| {
-        let id: String = row.get(0)?;
-        let user_name: String = row.get(1)?;
-        let is_expired = conn.query_row("SELECT is_expired(1?)", [id.clone()], |row| {
-            if let Ok(is_expired) = row.get::<usize, bool>(0) {
-                return Ok(is_expired);
-            }
-            return Ok(true)
-        })?;
-        if !is_expired {
-            return Ok(User{ username: user_name, user_session: id });
-        } else {
-            return Err(rusqlite::Error::QueryReturnedNoRows);
-        }
-    });
-
-    return match res {
-        Ok(user) => Ok(user),
-        Err(_) => Err(AuthServiceError::InvalidSessionToken),
-    };
+            let user = User {
+                user_id: row.get(2)?,
+                household_id: row.get(3)?,
+                role: Role::from_string(&row.get::<usize, String>(4)?).unwrap_or(Role::Viewer),
+                username: row.get(1)?,
+                user_session: row.get(0)?,
+            };
+            return Ok((
+                user,
+                row.get::<usize, Option<i64>>(5)?,
+                row.get::<usize, String>(6)?,
+            ));
+        },
+    );
+    let (user, lifetime_seconds, absolute_expiry) =
+        res.or(Err(AuthServiceError::InvalidSessionToken))?;
+    let config = SessionConfig::get();
+    if config.sliding {
+        // renewal never pushes a session past the absolute expiry it was created with
+        let lifetime = lifetime_seconds
+            .and_then(TimeDelta::try_seconds)
+            .unwrap_or(config.lifetime);
+        let renewed = Utc::now() + lifetime;
+        let expiry = match DateTime::parse_from_rfc3339(&absolute_expiry) {
+            Ok(cap) => renewed.min(cap.to_utc()),
+            Err(_) => renewed,
+        };
+        conn.execute(
+            "UPDATE users_sessions SET expiry = ?1 WHERE id = ?2",
+            params![expiry.to_rfc3339(), user.user_session],
+        )
+        .or(Err(AuthServiceError::InvalidSessionToken))?;
+    }
+    return Ok(user);
 }
 
 pub fn create_user(request_body: LoginRequest) -> Result<User, AuthServiceError> {
     let user_id = Uuid::new_v4().to_string();
-    let conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
-    let statement_result = conn.execute(
-        "INSERT INTO users (id, username, passHash) 
-                                             VALUES (?1,?2,?3);",
+    let household_id = Uuid::new_v4().to_string();
+    let pass_hash = hash_password(&request_body.password)?;
+    let mut conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
+    let tx = conn
+        .transaction()
+        .or(Err(AuthServiceError::FailedToRegister))?;
+    tx.execute(
+        "INSERT INTO households (id, name) VALUES (?1, ?2)",
+        [
+            household_id.clone(),
+            format!("{}'s household", request_body.username),
+        ],
+    )
+    .or(Err(AuthServiceError::FailedToRegister))?;
+    tx.execute(
+        "INSERT INTO users (id, username, passHash, household_id, role)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
         [
             user_id.clone(),
             request_body.username.clone(),
-            request_body.pass_hash,
+            pass_hash,
+            household_id,
+            Role::Owner.string(),
         ],
-    );
-    if statement_result.is_err() {
-        return Err(AuthServiceError::FailedToRegister);
-    }
-    let session_id = create_session_id(user_id.clone(), request_body.username);
+    )
+    .or(Err(AuthServiceError::FailedToRegister))?;
+    tx.commit().or(Err(AuthServiceError::FailedToRegister))?;
+    let session_id = create_session_id(user_id.clone(), &request_body);
     if let Ok(user) = session_id {
         return Ok(user);
     } else {
@@ -71,48 +147,149 @@ pub fn create_user(request_body: LoginRequest) -> Result<User, AuthServiceError>
     }
 }
 
-pub fn create_session_id(user_id: String, name: String) -> Result<User, AuthServiceError> {
+pub fn create_session_id(
+    user_id: String,
+    request: &LoginRequest,
+) -> Result<User, AuthServiceError> {
     let session_id = Uuid::new_v4().to_string();
+    let config = SessionConfig::get();
+    let lifetime = config.lifetime_for(request.remember_me).num_seconds();
+    let absolute_lifetime = config
+        .absolute_lifetime_for(request.remember_me)
+        .num_seconds();
     let conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
-    let expiry = conn
-        .query_row("SELECT get_expiry()", [], |row| {
-            return Ok(row.get::<usize, String>(0)?);
-        })
-        .or(Err(AuthServiceError::FailedToRegister))?;
+    let (household_id, role) = conn
+        .query_row(
+            "SELECT household_id, role FROM users WHERE id = ?1",
+            [&user_id],
+            |row| {
+                let role = Role::from_string(&row.get::<usize, String>(1)?).unwrap_or(Role::Viewer);
+                return Ok((row.get::<usize, String>(0)?, role));
+            },
+        )
+        .or(Err(AuthServiceError::FailedToAuthenticate))?;
     let res = conn.execute(
-        "INSERT INTO user_sessions(id, user_id, expiry) VALUES(1?, 2?, 3?)",
-        [session_id.clone(), user_id, expiry],
+        "INSERT INTO users_sessions
+         (id, user_id, expiry, device_label, created_at, lifetime_seconds, absolute_expiry)
+         VALUES (?1, ?2, get_expiry(?5), ?3, ?4, ?5, get_expiry(?6))",
+        params![
+            session_id,
+            user_id,
+            request.device_label,
+            Utc::now().to_rfc3339(),
+            lifetime,
+            absolute_lifetime
+        ],
     );
     match res {
         Ok(_) => Ok(User {
-            username: name,
+            user_id,
+            household_id,
+            role,
+            username: request.username.clone(),
             user_session: session_id,
         }),
         Err(_) => Err(AuthServiceError::FailedToAuthenticate),
     }
 }
 
-pub fn validate_request(request: actix_web::dev::ServiceRequest) -> Result<(), AuthServiceError> {
-    let session_id = request.headers().get("session_id");
-    if let Some(session_id) = session_id {
-        let conn = get_connection().or(Err(AuthServiceError::FailedToRegister))?;
-        let res = conn.query_row(
-            "SELECT is_expired(1?)",
-            [session_id
-                .to_str()
-                .or(Err(AuthServiceError::FailedToAuthenticate))?],
-            |row| Ok(row.get::<usize, bool>(0)?),
-        );
-        return match res {
-            Ok(res) => {
-                if res {
-                    return Ok(());
-                } else {
-                    return Err(AuthServiceError::InvalidSessionToken);
-                }
-            }
-            Err(_) => Err(AuthServiceError::FailedToRegister),
-        };
+pub fn get_active_sessions(user: &User) -> Result<Vec<SessionInfo>, AuthServiceError> {
+    let conn = get_connection().or(Err(AuthServiceError::InvalidSessionToken))?;
+    let mut statement = conn
+        .prepare(
+            "SELECT id, device_label, created_at, expiry FROM users_sessions
+             WHERE user_id = ?1 AND NOT is_expired(expiry)",
+        )
+        .or(Err(AuthServiceError::SessionNotFound))?;
+    let sessions = statement
+        .query_map([&user.user_id], |row| {
+            let id: String = row.get(0)?;
+            return Ok(SessionInfo {
+                current: id == user.user_session,
+                id,
+                device_label: row.get(1)?,
+                created_at: row.get(2)?,
+                expiry: row.get(3)?,
+            });
+        })
+        .or(Err(AuthServiceError::SessionNotFound))?;
+    return Ok(sessions.filter_map(|session| session.ok()).collect());
+}
+
+pub fn revoke_session(user: &User, session_id: &str) -> Result<(), AuthServiceError> {
+    let conn = get_connection().or(Err(AuthServiceError::InvalidSessionToken))?;
+    let removed = conn
+        .execute(
+            "DELETE FROM users_sessions WHERE id = ?1 AND user_id = ?2",
+            [session_id, &user.user_id],
+        )
+        .or(Err(AuthServiceError::SessionNotFound))?;
+    if removed == 0 {
+        return Err(AuthServiceError::SessionNotFound);
+    }
+    return Ok(());
+}
+
+// Calendar clients can't send headers, so the feed gets its own token that only
+// opens the read only feed and can be rotated without touching any sessions
+pub fn create_calendar_token(user: &User) -> Result<String, AuthServiceError> {
+    let conn = get_connection().or(Err(AuthServiceError::InvalidSessionToken))?;
+    let token = Uuid::new_v4().simple().to_string();
+    conn.execute(
+        "UPDATE users SET calendar_token = ?1 WHERE id = ?2",
+        [token.as_str(), user.user_id.as_str()],
+    )
+    .or(Err(AuthServiceError::InvalidSessionToken))?;
+    return Ok(token);
+}
+
+pub fn revoke_calendar_token(user: &User) -> Result<(), AuthServiceError> {
+    let conn = get_connection().or(Err(AuthServiceError::InvalidSessionToken))?;
+    conn.execute(
+        "UPDATE users SET calendar_token = NULL WHERE id = ?1",
+        [user.user_id.as_str()],
+    )
+    .or(Err(AuthServiceError::InvalidSessionToken))?;
+    return Ok(());
+}
+
+pub fn validate_calendar_token(token: &str) -> Result<User, AuthServiceError> {
+    let conn = get_connection().or(Err(AuthServiceError::InvalidCalendarToken))?;
+    return conn
+        .query_row(
+            "SELECT id, household_id, role, username FROM users WHERE calendar_token = ?1",
+            [token],
+            |row| {
+                return Ok(User {
+                    user_id: row.get(0)?,
+                    household_id: row.get(1)?,
+                    role: Role::from_string(&row.get::<usize, String>(2)?).unwrap_or(Role::Viewer),
+                    username: row.get(3)?,
+                    user_session: String::new(),
+                });
+            },
+        )
+        .or(Err(AuthServiceError::InvalidCalendarToken));
+}
+
+pub async fn validate_request(request: &ServiceRequest) -> Result<User, AuthServiceError> {
+    let header_session = request
+        .headers()
+        .get(SESSION_KEY)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let session_id = header_session.or(request
+        .cookie(SESSION_KEY)
+        .map(|cookie| cookie.value().to_string()));
+    return match session_id {
+        Some(session_id) => validate_user_session(session_id).await,
+        None => Err(AuthServiceError::InvalidSessionToken),
+    };
+}
+
+pub fn authorize(user: &User, permission: Permission) -> Result<(), AuthServiceError> {
+    if user.role.allows(permission) {
+        return Ok(());
     }
     return Err(AuthServiceError::GenerallyForbiden);
 }
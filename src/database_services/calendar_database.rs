@@ -0,0 +1,67 @@
+use crate::data_models::auth_models::User;
+use crate::data_models::calendar::CalendarEvent;
+use crate::data_models::loans::DATE_FORMAT;
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::game_database::GameDataBase;
+use crate::database_services::loan_database::LoanDataBase;
+use crate::database_services::movie_database::MovieDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::database_services::wishlist_database::WishlistDataBase;
+use crate::errors::servive_errors::ServiceError;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+pub struct CalendarDataBase;
+impl CalendarDataBase {
+    pub async fn get_events(user: &User) -> Result<Vec<CalendarEvent>, ServiceError> {
+        let controls = ParentalControls::for_user(user).await?;
+        let mut titles: HashMap<(MediaKind, String), String> = HashMap::new();
+        for game in GameDataBase::get_games(&user.household_id)
+            .await?
+            .unwrap_or_default()
+        {
+            if controls.allows_game(&game) {
+                titles.insert((MediaKind::Game, game.id), game.title);
+            }
+        }
+        for movie in MovieDataBase::get_all_movies(&user.household_id)
+            .await?
+            .unwrap_or_default()
+        {
+            if controls.allows_movie(&movie) {
+                titles.insert((MediaKind::Movie, movie.id), movie.title);
+            }
+        }
+        let mut events = vec![];
+        for loan in LoanDataBase::get_active_loans(&user.household_id).await? {
+            let title = titles.get(&(loan.media_kind, loan.media_id.clone()));
+            let due_on = loan
+                .due_on
+                .as_deref()
+                .and_then(|due_on| NaiveDate::parse_from_str(due_on, DATE_FORMAT).ok());
+            if let (Some(title), Some(date)) = (title, due_on) {
+                events.push(CalendarEvent {
+                    uid: format!("loan-{}@kellum-library", loan.id),
+                    date,
+                    summary: format!("{title} due back from {}", loan.borrower),
+                    description: Some(format!("Lent on {}", loan.lent_on)),
+                });
+            }
+        }
+        for item in WishlistDataBase::get_items(&user.household_id, None, false).await? {
+            let release_date = item
+                .release_date
+                .as_deref()
+                .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok());
+            if let Some(date) = release_date {
+                events.push(CalendarEvent {
+                    uid: format!("wish-{}@kellum-library", item.id),
+                    date,
+                    summary: format!("{} releases", item.title),
+                    description: Some(format!("On {}'s wishlist", item.username)),
+                });
+            }
+        }
+        return Ok(events);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::data_models::contacts::Contact;
+use crate::database_services::database_utilities::get_connection;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::{params, OptionalExtension, Row};
+use uuid::Uuid;
+
+fn contact_from_row(row: &Row) -> Result<Contact, rusqlite::Error> {
+    return Ok(Contact {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        email: row.get(2)?,
+        notes: row.get(3)?,
+    });
+}
+
+pub struct ContactDataBase;
+impl ContactDataBase {
+    pub async fn get_contacts(household_id: &str) -> Result<Vec<Contact>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, name, email, notes FROM contacts
+                 WHERE household_id=?1 ORDER BY name COLLATE NOCASE",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let contacts = statement
+            .query_map([household_id], contact_from_row)
+            .or(Err(ServiceError::ContactNotFound))?;
+        return Ok(contacts.filter_map(|contact| contact.ok()).collect());
+    }
+
+    pub async fn get_contact(
+        household_id: &str,
+        contact_id: &str,
+    ) -> Result<Contact, ServiceError> {
+        let conn = get_connection()?;
+        return conn
+            .query_row(
+                "SELECT id, name, email, notes FROM contacts WHERE id=?1 AND household_id=?2",
+                [contact_id, household_id],
+                contact_from_row,
+            )
+            .optional()
+            .or(Err(ServiceError::ConnectionFailure))?
+            .ok_or(ServiceError::ContactNotFound);
+    }
+
+    pub async fn add_contact(
+        household_id: &str,
+        mut contact: Contact,
+    ) -> Result<Contact, ServiceError> {
+        if contact.name.trim().is_empty() {
+            return Err(ServiceError::InvalidContact);
+        }
+        contact.id = Uuid::new_v4().to_string();
+        let conn = get_connection()?;
+        conn.execute(
+            "INSERT INTO contacts (id, household_id, name, email, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![contact.id, household_id, contact.name, contact.email, contact.notes],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(contact);
+    }
+
+    pub async fn update_contact(
+        household_id: &str,
+        contact_id: &str,
+        contact: Contact,
+    ) -> Result<bool, ServiceError> {
+        if contact.name.trim().is_empty() {
+            return Err(ServiceError::InvalidContact);
+        }
+        let conn = get_connection()?;
+        let updated = conn
+            .execute(
+                "UPDATE contacts SET name=?1, email=?2, notes=?3 WHERE id=?4 AND household_id=?5",
+                params![
+                    contact.name,
+                    contact.email,
+                    contact.notes,
+                    contact_id,
+                    household_id
+                ],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if updated == 0 {
+            return Err(ServiceError::ContactNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn delete_contact(
+        household_id: &str,
+        contact_id: &str,
+    ) -> Result<bool, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        // loans keep the borrower's name so history still reads sensibly
+        tx.execute(
+            "UPDATE loans SET contact_id=NULL WHERE contact_id=?1 AND household_id=?2",
+            [contact_id, household_id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        let removed = tx
+            .execute(
+                "DELETE FROM contacts WHERE id=?1 AND household_id=?2",
+                [contact_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if removed == 0 {
+            return Err(ServiceError::ContactNotFound);
+        }
+        tx.commit().or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(true);
+    }
+}
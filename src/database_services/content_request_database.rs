@@ -0,0 +1,201 @@
+use crate::data_models::content_requests::{
+    ContentRequest, Decision, NewContentRequest, RequestDecision, RequestStatus,
+};
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::database_utilities::get_connection;
+use crate::errors::servive_errors::ServiceError;
+use chrono::Utc;
+use rusqlite::{params, Row};
+use uuid::Uuid;
+
+const REQUEST_COLUMNS: &str = "id, user_id, media_kind, media_id, status, note, created_at";
+
+fn request_from_row(row: &Row) -> Result<ContentRequest, rusqlite::Error> {
+    let kind: String = row.get(2)?;
+    let status: String = row.get(4)?;
+    return Ok(ContentRequest {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        media_kind: MediaKind::from_string(&kind).unwrap_or(MediaKind::Game),
+        media_id: row.get(3)?,
+        status: RequestStatus::from_string(&status).unwrap_or(RequestStatus::Pending),
+        note: row.get(5)?,
+        created_at: row.get(6)?,
+    });
+}
+
+pub struct ContentRequestDataBase;
+impl ContentRequestDataBase {
+    pub async fn create_request(
+        household_id: &str,
+        user_id: &str,
+        request: NewContentRequest,
+    ) -> Result<ContentRequest, ServiceError> {
+        let conn = get_connection()?;
+        let pending = conn
+            .query_row(
+                "SELECT COUNT(*) FROM content_requests
+                 WHERE household_id=?1 AND user_id=?2 AND media_kind=?3 AND media_id=?4
+                 AND status=?5",
+                params![
+                    household_id,
+                    user_id,
+                    request.media_kind.string(),
+                    request.media_id,
+                    RequestStatus::Pending.string()
+                ],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if pending > 0 {
+            return Err(ServiceError::DuplicateRequest);
+        }
+        let new_request = ContentRequest {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            media_kind: request.media_kind,
+            media_id: request.media_id,
+            status: RequestStatus::Pending,
+            note: request.note,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        conn.execute(
+            "INSERT INTO content_requests
+             (id, household_id, user_id, media_kind, media_id, status, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                new_request.id,
+                household_id,
+                new_request.user_id,
+                new_request.media_kind.string(),
+                new_request.media_id,
+                new_request.status.string(),
+                new_request.note,
+                new_request.created_at
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(new_request);
+    }
+
+    pub async fn get_requests(
+        household_id: &str,
+        user_id: Option<&str>,
+        status: Option<RequestStatus>,
+    ) -> Result<Vec<ContentRequest>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {REQUEST_COLUMNS} FROM content_requests
+                 WHERE household_id=?1 AND (?2 IS NULL OR user_id=?2) AND (?3 IS NULL OR status=?3)
+                 ORDER BY created_at"
+            ))
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let requests = statement
+            .query_map(
+                params![household_id, user_id, status.map(|status| status.string())],
+                request_from_row,
+            )
+            .or(Err(ServiceError::RequestNotFound))?;
+        return Ok(requests.filter_map(|request| request.ok()).collect());
+    }
+
+    // The status change, the exception it grants and the audit entry all land together
+    pub async fn decide_request(
+        household_id: &str,
+        request_id: &str,
+        decided_by: &str,
+        approved: bool,
+        decision: Decision,
+    ) -> Result<RequestDecision, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let request = tx
+            .query_row(
+                &format!(
+                    "SELECT {REQUEST_COLUMNS} FROM content_requests
+                     WHERE id=?1 AND household_id=?2 AND status=?3"
+                ),
+                [request_id, household_id, &RequestStatus::Pending.string()],
+                request_from_row,
+            )
+            .or(Err(ServiceError::RequestNotFound))?;
+        let status = match (approved, decision.permanent) {
+            (false, _) => RequestStatus::Denied,
+            (true, false) => RequestStatus::ApprovedOnce,
+            (true, true) => RequestStatus::ApprovedPermanently,
+        };
+        tx.execute(
+            "UPDATE content_requests SET status=?1 WHERE id=?2",
+            [status.string().as_str(), request_id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        if approved {
+            tx.execute(
+                "INSERT INTO rating_exceptions (id, household_id, user_id, media_kind, media_id, one_time)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    household_id,
+                    request.user_id,
+                    request.media_kind.string(),
+                    request.media_id,
+                    !decision.permanent
+                ],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        }
+        let audit = RequestDecision {
+            id: Uuid::new_v4().to_string(),
+            request_id: request_id.to_string(),
+            decided_by: decided_by.to_string(),
+            decision: status,
+            note: decision.note,
+            decided_at: Utc::now().to_rfc3339(),
+        };
+        tx.execute(
+            "INSERT INTO content_request_decisions
+             (id, household_id, request_id, decided_by, decision, note, decided_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                audit.id,
+                household_id,
+                audit.request_id,
+                audit.decided_by,
+                audit.decision.string(),
+                audit.note,
+                audit.decided_at
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        tx.commit().or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(audit);
+    }
+
+    pub async fn get_decisions(household_id: &str) -> Result<Vec<RequestDecision>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, request_id, decided_by, decision, note, decided_at
+                 FROM content_request_decisions WHERE household_id=?1 ORDER BY decided_at",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let decisions = statement
+            .query_map([household_id], |row| {
+                let decision: String = row.get(3)?;
+                return Ok(RequestDecision {
+                    id: row.get(0)?,
+                    request_id: row.get(1)?,
+                    decided_by: row.get(2)?,
+                    decision: RequestStatus::from_string(&decision)
+                        .unwrap_or(RequestStatus::Denied),
+                    note: row.get(4)?,
+                    decided_at: row.get(5)?,
+                });
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(decisions.filter_map(|decision| decision.ok()).collect());
+    }
+}
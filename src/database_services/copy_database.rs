@@ -0,0 +1,373 @@
+use crate::data_models::copies::{Condition, CopyDetails, CopyFilter, MediaCopy, NewCopy, Parts};
+use crate::data_models::game::Game;
+use crate::data_models::hardware::{valid_region_code, VideoStandard};
+use crate::data_models::lookup::MediaKind;
+use crate::data_models::movie::Movie;
+use crate::database_services::database_utilities::{get_connection, validate_date};
+use crate::database_services::loan_database::LoanDataBase;
+use crate::database_services::location_database::LocationTree;
+use crate::database_services::movie_database::MovieDataBase;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::{params, Row};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const COPY_QUERY: &str = "SELECT id, media_kind, media_id, owner_id, condition, location_id,
+    purchased_on, purchase_price_cents, purchased_from, notes, has_media, has_box, has_manual,
+    sealed, media_condition, box_condition, manual_condition, video_standard, region_code
+    FROM copies";
+
+fn condition_at(row: &Row, index: usize) -> Result<Option<Condition>, rusqlite::Error> {
+    return Ok(row
+        .get::<usize, Option<String>>(index)?
+        .and_then(|condition| Condition::from_string(&condition)));
+}
+
+fn copy_from_row(row: &Row) -> Result<MediaCopy, rusqlite::Error> {
+    // parts are only tracked once someone has said whether the media is there
+    let parts = match row.get::<usize, Option<bool>>(10)? {
+        Some(has_media) => Some(Parts {
+            has_media,
+            has_box: row.get::<usize, Option<bool>>(11)?.unwrap_or(false),
+            has_manual: row.get::<usize, Option<bool>>(12)?.unwrap_or(false),
+            sealed: row.get::<usize, Option<bool>>(13)?.unwrap_or(false),
+            media_condition: condition_at(row, 14)?,
+            box_condition: condition_at(row, 15)?,
+            manual_condition: condition_at(row, 16)?,
+        }),
+        None => None,
+    };
+    return Ok(MediaCopy {
+        id: row.get(0)?,
+        media_kind: MediaKind::from_string(&row.get::<usize, String>(1)?)
+            .unwrap_or(MediaKind::Game),
+        media_id: row.get(2)?,
+        details: CopyDetails {
+            owner_id: row.get(3)?,
+            condition: condition_at(row, 4)?,
+            location_id: row.get(5)?,
+            purchased_on: row.get(6)?,
+            purchase_price_cents: row.get(7)?,
+            purchased_from: row.get(8)?,
+            notes: row.get(9)?,
+            parts: parts.clone(),
+            video_standard: row
+                .get::<usize, Option<String>>(17)?
+                .and_then(|standard| VideoStandard::from_string(&standard)),
+            region_code: row.get(18)?,
+        },
+        location: None,
+        completeness: parts.map(|parts| parts.completeness()),
+        warning: None,
+        loan: None,
+    });
+}
+
+// A CopyFilter resolved against the copies we hold, used the same way as ParentalControls
+pub struct CopyMatches {
+    filter: CopyFilter,
+    held: Option<HashSet<(MediaKind, String)>>,
+}
+
+impl CopyMatches {
+    pub async fn for_filter(household_id: &str, filter: CopyFilter) -> Result<Self, ServiceError> {
+        let held = if filter.filters_copies() {
+            Some(
+                CopyDataBase::get_household_copies(household_id)
+                    .await?
+                    .into_iter()
+                    .filter(|copy| filter.matches(copy))
+                    .map(|copy| (copy.media_kind, copy.media_id))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        return Ok(CopyMatches { filter, held });
+    }
+
+    fn holds(&self, kind: MediaKind, id: &str) -> bool {
+        return match &self.held {
+            Some(held) => held.contains(&(kind, id.to_string())),
+            None => true,
+        };
+    }
+
+    pub fn allows_game(&self, game: &Game) -> bool {
+        let retro_matches = match self.filter.retro {
+            Some(retro) => game.platform.is_retro() == retro,
+            None => true,
+        };
+        return retro_matches && self.holds(MediaKind::Game, &game.id);
+    }
+
+    pub fn allows_movie(&self, movie: &Movie) -> bool {
+        return self.filter.retro != Some(true) && self.holds(MediaKind::Movie, &movie.id);
+    }
+}
+
+pub struct CopyDataBase;
+impl CopyDataBase {
+    pub async fn get_copies(
+        household_id: &str,
+        kind: MediaKind,
+        media_id: &str,
+    ) -> Result<Vec<MediaCopy>, ServiceError> {
+        let tree = LocationTree::for_household(household_id).await?;
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "{COPY_QUERY} WHERE household_id=?1 AND media_kind=?2 AND media_id=?3"
+            ))
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let copies: Vec<MediaCopy> = statement
+            .query_map([household_id, &kind.string(), media_id], copy_from_row)
+            .or(Err(ServiceError::CopyNotFound))?
+            .filter_map(|copy| copy.ok())
+            .collect();
+        let mut located = Vec::with_capacity(copies.len());
+        for mut copy in copies {
+            copy.location = copy
+                .details
+                .location_id
+                .as_ref()
+                .and_then(|location_id| tree.path(location_id));
+            copy.loan = LoanDataBase::current_loan(household_id, &copy.id).await?;
+            located.push(copy);
+        }
+        return Ok(located);
+    }
+
+    pub async fn get_household_copies(household_id: &str) -> Result<Vec<MediaCopy>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(&format!("{COPY_QUERY} WHERE household_id=?1"))
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let copies = statement
+            .query_map([household_id], copy_from_row)
+            .or(Err(ServiceError::CopyNotFound))?;
+        return Ok(copies.filter_map(|copy| copy.ok()).collect());
+    }
+
+    pub async fn get_copy(household_id: &str, copy_id: &str) -> Result<MediaCopy, ServiceError> {
+        let conn = get_connection()?;
+        let mut copy = conn
+            .query_row(
+                &format!("{COPY_QUERY} WHERE household_id=?1 AND id=?2"),
+                [household_id, copy_id],
+                copy_from_row,
+            )
+            .or(Err(ServiceError::CopyNotFound))?;
+        if let Some(location_id) = &copy.details.location_id {
+            copy.location = LocationTree::for_household(household_id)
+                .await?
+                .path(location_id);
+        }
+        return Ok(copy);
+    }
+
+    pub async fn add_copy(household_id: &str, request: NewCopy) -> Result<MediaCopy, ServiceError> {
+        let (table, not_found) = match request.media_kind {
+            MediaKind::Game => ("games", ServiceError::GameNotFound),
+            MediaKind::Movie => ("movies", ServiceError::MovieNotFound),
+        };
+        let details = CopyDataBase::validate_details(
+            household_id,
+            request.media_kind,
+            &request.media_id,
+            request.details,
+        )
+        .await?;
+        let parts = &details.parts;
+        let id = Uuid::new_v4().to_string();
+        let conn = get_connection()?;
+        let exists = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {table} WHERE id=?1 AND household_id=?2"),
+                [request.media_id.as_str(), household_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if exists == 0 {
+            return Err(not_found);
+        }
+        conn.execute(
+            "INSERT INTO copies (id, household_id, media_kind, media_id, owner_id, condition,
+             location_id, purchased_on, purchase_price_cents, purchased_from, notes, has_media,
+             has_box, has_manual, sealed, media_condition, box_condition, manual_condition,
+             video_standard, region_code)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+             ?18, ?19, ?20)",
+            params![
+                id,
+                household_id,
+                request.media_kind.string(),
+                request.media_id,
+                details.owner_id,
+                details.condition.map(|condition| condition.string()),
+                details.location_id,
+                details.purchased_on,
+                details.purchase_price_cents,
+                details.purchased_from,
+                details.notes,
+                parts.as_ref().map(|parts| parts.has_media),
+                parts.as_ref().map(|parts| parts.has_box),
+                parts.as_ref().map(|parts| parts.has_manual),
+                parts.as_ref().map(|parts| parts.sealed),
+                parts
+                    .as_ref()
+                    .and_then(|parts| parts.media_condition.map(|condition| condition.string())),
+                parts
+                    .as_ref()
+                    .and_then(|parts| parts.box_condition.map(|condition| condition.string())),
+                parts
+                    .as_ref()
+                    .and_then(|parts| parts.manual_condition.map(|condition| condition.string())),
+                details.video_standard.map(|standard| standard.string()),
+                details.region_code
+            ],
+        )
+        .or(Err(ServiceError::FailedToSaveCopy))?;
+        return CopyDataBase::get_copy(household_id, &id).await;
+    }
+
+    pub async fn update_copy(
+        household_id: &str,
+        copy_id: &str,
+        details: CopyDetails,
+    ) -> Result<bool, ServiceError> {
+        let copy = CopyDataBase::get_copy(household_id, copy_id).await?;
+        let details =
+            CopyDataBase::validate_details(household_id, copy.media_kind, &copy.media_id, details)
+                .await?;
+        let parts = &details.parts;
+        let conn = get_connection()?;
+        let updated = conn
+            .execute(
+                "UPDATE copies SET owner_id=?1, condition=?2, location_id=?3, purchased_on=?4,
+                 purchase_price_cents=?5, purchased_from=?6, notes=?7, has_media=?8, has_box=?9,
+                 has_manual=?10, sealed=?11, media_condition=?12, box_condition=?13,
+                 manual_condition=?14, video_standard=?15, region_code=?16
+                 WHERE id=?17 AND household_id=?18",
+                params![
+                    details.owner_id,
+                    details.condition.map(|condition| condition.string()),
+                    details.location_id,
+                    details.purchased_on,
+                    details.purchase_price_cents,
+                    details.purchased_from,
+                    details.notes,
+                    parts.as_ref().map(|parts| parts.has_media),
+                    parts.as_ref().map(|parts| parts.has_box),
+                    parts.as_ref().map(|parts| parts.has_manual),
+                    parts.as_ref().map(|parts| parts.sealed),
+                    parts.as_ref().and_then(|parts| parts
+                        .media_condition
+                        .map(|condition| condition.string())),
+                    parts
+                        .as_ref()
+                        .and_then(|parts| parts.box_condition.map(|condition| condition.string())),
+                    parts.as_ref().and_then(|parts| parts
+                        .manual_condition
+                        .map(|condition| condition.string())),
+                    details.video_standard.map(|standard| standard.string()),
+                    details.region_code,
+                    copy_id,
+                    household_id
+                ],
+            )
+            .or(Err(ServiceError::FailedToSaveCopy))?;
+        if updated == 0 {
+            return Err(ServiceError::CopyNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn delete_copy(household_id: &str, copy_id: &str) -> Result<bool, ServiceError> {
+        if LoanDataBase::current_loan(household_id, copy_id)
+            .await?
+            .is_some()
+        {
+            return Err(ServiceError::AlreadyOnLoan);
+        }
+        let conn = get_connection()?;
+        // the last copy goes with its title, through deleting the title
+        let siblings = conn
+            .query_row(
+                "SELECT COUNT(*) FROM copies AS other
+                 INNER JOIN copies AS this
+                 ON other.media_kind = this.media_kind AND other.media_id = this.media_id
+                 WHERE this.id=?1 AND this.household_id=?2 AND other.household_id=?2",
+                [copy_id, household_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if siblings == 1 {
+            return Err(ServiceError::LastCopy);
+        }
+        // returned loans keep their history, just without the copy that is gone
+        conn.execute(
+            "UPDATE loans SET copy_id=NULL WHERE copy_id=?1 AND household_id=?2",
+            [copy_id, household_id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        let removed = conn
+            .execute(
+                "DELETE FROM copies WHERE id=?1 AND household_id=?2",
+                [copy_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if removed == 0 {
+            return Err(ServiceError::CopyNotFound);
+        }
+        return Ok(true);
+    }
+
+    // owners have to be in the household and locations have to be ones we know about
+    async fn validate_details(
+        household_id: &str,
+        kind: MediaKind,
+        media_id: &str,
+        mut details: CopyDetails,
+    ) -> Result<CopyDetails, ServiceError> {
+        details.purchased_on = validate_date(details.purchased_on)?;
+        // region codes are a disc thing, DVDs use digits and Blu-rays use letters
+        if let Some(code) = &details.region_code {
+            let code = code.trim().to_uppercase();
+            let format = match kind {
+                MediaKind::Game => None,
+                MediaKind::Movie => {
+                    MovieDataBase::get_movie_with_id(media_id.to_string(), household_id)
+                        .await?
+                        .map(|movie| movie.format)
+                }
+            };
+            match format {
+                Some(format) if valid_region_code(&format, &code) => {
+                    details.region_code = Some(code)
+                }
+                _ => return Err(ServiceError::InvalidRegionCode),
+            }
+        }
+        if let Some(location_id) = &details.location_id {
+            LocationTree::for_household(household_id)
+                .await?
+                .get(location_id)
+                .ok_or(ServiceError::LocationNotFound)?;
+        }
+        if let Some(owner_id) = &details.owner_id {
+            let conn = get_connection()?;
+            let is_member = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM users WHERE id=?1 AND household_id=?2",
+                    [owner_id.as_str(), household_id],
+                    |row| row.get::<usize, usize>(0),
+                )
+                .or(Err(ServiceError::ConnectionFailure))?;
+            if is_member == 0 {
+                return Err(ServiceError::MemberNotFound);
+            }
+        }
+        return Ok(details);
+    }
+}
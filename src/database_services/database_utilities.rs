@@ -1,7 +1,99 @@
+use crate::data_models::content_requests::RequestStatus;
+use crate::data_models::loans::DATE_FORMAT;
+use crate::data_models::lookup::MediaKind;
 use crate::errors::servive_errors::ServiceError;
-use chrono::{self, offset::LocalResult, DateTime, TimeDelta, Utc};
-use rusqlite::{functions::FunctionFlags, Connection};
-use std::{env, sync::Arc};
+use chrono::{self, DateTime, NaiveDate, TimeDelta, Utc};
+use rusqlite::{functions::FunctionFlags, params, Connection, Transaction};
+use std::env;
+
+// Tables that point at a game or movie through media_kind/media_id columns,
+// merging duplicates moves these rows over to the record that is kept
+pub const MEDIA_REFERENCE_TABLES: &[&str] = &[
+    "rating_exceptions",
+    "content_requests",
+    "loans",
+    "wishlist_items",
+    "copies",
+    "watch_log",
+];
+
+pub fn reassign_media_references(
+    tx: &Transaction,
+    kind: MediaKind,
+    household_id: &str,
+    survivor_id: &str,
+    duplicate_id: &str,
+) -> Result<(), rusqlite::Error> {
+    // someone who asked for both records only needs the request already on the survivor
+    tx.execute(
+        "DELETE FROM content_requests AS duplicate
+         WHERE household_id=?1 AND media_kind=?2 AND media_id=?3 AND status=?5
+         AND EXISTS (SELECT 1 FROM content_requests AS kept
+                     WHERE kept.household_id=?1 AND kept.media_kind=?2 AND kept.media_id=?4
+                     AND kept.status=?5 AND kept.user_id=duplicate.user_id)",
+        [
+            household_id,
+            &kind.string(),
+            duplicate_id,
+            survivor_id,
+            &RequestStatus::Pending.string(),
+        ],
+    )?;
+    for table in MEDIA_REFERENCE_TABLES {
+        tx.execute(
+            &format!(
+                "UPDATE {table} SET media_id=?1
+                 WHERE household_id=?2 AND media_kind=?3 AND media_id=?4"
+            ),
+            [survivor_id, household_id, &kind.string(), duplicate_id],
+        )?;
+    }
+    return Ok(());
+}
+
+// Clears what points at a title, or at every title of a kind when no id is given,
+// before the title itself is deleted. Wishlist entries are kept and only unlinked
+pub fn remove_media_references(
+    tx: &Transaction,
+    kind: MediaKind,
+    household_id: &str,
+    id: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "DELETE FROM content_request_decisions WHERE request_id IN
+         (SELECT id FROM content_requests
+          WHERE household_id=?1 AND media_kind=?2 AND (?3 IS NULL OR media_id=?3))",
+        params![household_id, kind.string(), id],
+    )?;
+    for table in MEDIA_REFERENCE_TABLES {
+        let statement = match *table {
+            "wishlist_items" => format!(
+                "UPDATE {table} SET media_id=NULL
+                 WHERE household_id=?1 AND media_kind=?2 AND (?3 IS NULL OR media_id=?3)"
+            ),
+            _ => format!(
+                "DELETE FROM {table}
+                 WHERE household_id=?1 AND media_kind=?2 AND (?3 IS NULL OR media_id=?3)"
+            ),
+        };
+        tx.execute(&statement, params![household_id, kind.string(), id])?;
+    }
+    return Ok(());
+}
+
+pub fn today() -> String {
+    return Utc::now().date_naive().format(DATE_FORMAT).to_string();
+}
+
+pub fn validate_date(date: Option<String>) -> Result<Option<String>, ServiceError> {
+    return match date {
+        Some(date) => match NaiveDate::parse_from_str(&date, DATE_FORMAT) {
+            Ok(parsed) => Ok(Some(parsed.format(DATE_FORMAT).to_string())),
+            Err(_) => Err(ServiceError::InvalidDate),
+        },
+        None => Ok(None),
+    };
+}
 
 pub fn get_connection() -> Result<rusqlite::Connection, ServiceError> {
     let db_path = env::var("DB_PATH").unwrap_or("kellum_library.db".to_string());
@@ -15,41 +107,22 @@ pub fn get_connection() -> Result<rusqlite::Connection, ServiceError> {
 }
 
 fn add_is_expired(conn: &Connection) -> Result<(), ServiceError> {
-    conn.create_scalar_function(
-        "is_expired",
-        1,
-        FunctionFlags::SQLITE_DETERMINISTIC | FunctionFlags::SQLITE_UTF8,
-        move |ctx| {
-            let date: Result<Arc<chrono::DateTime<Utc>>, rusqlite::Error> =
-                ctx.get_or_create_aux(0, |value| {
-                    let str_val = value.as_str().unwrap();
-                    let fixed_date = DateTime::parse_from_rfc3339(str_val).unwrap();
-                    return Ok::<chrono::DateTime<Utc>, rusqlite::Error>(fixed_date.to_utc());
-                });
-            let date: chrono::DateTime<Utc> = (*date.unwrap()).into();
-            return Ok(date < Utc::now());
-        },
-    )
+    conn.create_scalar_function("is_expired", 1, FunctionFlags::SQLITE_UTF8, move |ctx| {
+        let expiry = ctx.get::<String>(0)?;
+        return match DateTime::parse_from_rfc3339(&expiry) {
+            Ok(expiry) => Ok(expiry.to_utc() < Utc::now()),
+            Err(_) => Ok(true),
+        };
+    })
     .or(Err(ServiceError::ConnectionFailure))
 }
 
+// get_expiry(seconds) gives the rfc3339 timestamp that many seconds from now
 fn add_get_expiry(conn: &Connection) -> Result<(), ServiceError> {
-    conn.create_scalar_function(
-        "get_expiry",
-        0,
-        FunctionFlags::SQLITE_DETERMINISTIC | FunctionFlags::SQLITE_UTF8,
-        move |_ctx| {
-            let now = Utc::now();
-            let duration = TimeDelta::hours(2);
-            let expiry_time = now.time().overflowing_add_signed(duration).0;
-            let expires_at: DateTime<Utc> = match now.with_time(expiry_time) {
-                LocalResult::Single(time) => time,
-                LocalResult::None => now,
-                LocalResult::Ambiguous(early_time, _) => early_time,
-            };
-            let str_val = expires_at.to_rfc3339();
-            return Ok(str_val);
-        },
-    )
+    conn.create_scalar_function("get_expiry", 1, FunctionFlags::SQLITE_UTF8, move |ctx| {
+        let lifetime = TimeDelta::seconds(ctx.get::<i64>(0)?);
+        let expires_at = Utc::now() + lifetime;
+        return Ok(expires_at.to_rfc3339());
+    })
     .or(Err(ServiceError::ConnectionFailure))
 }
@@ -1,29 +1,115 @@
-use crate::data_models::game::{ESRBRating, Game, PlatformType};
-use crate::database_services::database_utilities::get_connection;
+use crate::data_models::game::{
+    ESRBRating, Game, PlatformType, PlayerCounts, RatingSystem, RegionalRating,
+};
+use crate::data_models::lookup::{MediaKind, MergeRequest};
+use crate::database_services::database_utilities::{
+    get_connection, reassign_media_references, remove_media_references,
+};
+use crate::database_services::title_matching::{
+    normalize_title, title_similarity, LIKELY_SAME_THRESHOLD, MATCH_THRESHOLD,
+};
 use crate::errors::servive_errors::ServiceError;
+use rusqlite::OptionalExtension;
+use rusqlite::{params, Connection, Row};
+use std::collections::HashMap;
+
+fn rating_from_row(row: &Row) -> Result<Option<ESRBRating>, rusqlite::Error> {
+    return Ok(row
+        .get::<usize, Option<String>>(3)?
+        .and_then(|rating| ESRBRating::rating_from_string(&rating)));
+}
+
+fn players_from_row(row: &Row) -> Result<PlayerCounts, rusqlite::Error> {
+    return Ok(PlayerCounts {
+        min_players: row.get(4)?,
+        max_local_players: row.get(5)?,
+        couch_coop: row.get(6)?,
+        online: row.get(7)?,
+        max_online_players: row.get(8)?,
+    });
+}
+
+// Ratings from the other boards keyed by game, for one game or the whole household
+fn regional_ratings(
+    conn: &Connection,
+    household_id: &str,
+    game_id: Option<&str>,
+) -> Result<HashMap<String, Vec<RegionalRating>>, ServiceError> {
+    let mut statement = conn
+        .prepare(
+            "SELECT game_id, system, rating FROM game_ratings
+             WHERE household_id=?1 AND (?2 IS NULL OR game_id=?2) ORDER BY system",
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+    let rows = statement
+        .query_map(params![household_id, game_id], |row| {
+            return Ok((
+                row.get::<usize, String>(0)?,
+                row.get::<usize, String>(1)?,
+                row.get::<usize, String>(2)?,
+            ));
+        })
+        .or(Err(ServiceError::ConnectionFailure))?;
+    let mut ratings: HashMap<String, Vec<RegionalRating>> = HashMap::new();
+    for (game_id, system, rating) in rows.filter_map(|row| row.ok()) {
+        if let Some(system) = RatingSystem::from_string(&system) {
+            ratings
+                .entry(game_id)
+                .or_default()
+                .push(RegionalRating { system, rating });
+        }
+    }
+    return Ok(ratings);
+}
+
+fn save_regional_ratings(
+    conn: &Connection,
+    household_id: &str,
+    game: &Game,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM game_ratings WHERE game_id=?1 AND household_id=?2",
+        [game.id.as_str(), household_id],
+    )?;
+    for regional in &game.regional_ratings {
+        conn.execute(
+            "INSERT INTO game_ratings (game_id, household_id, system, rating)
+             VALUES (?1, ?2, ?3, ?4)",
+            [
+                game.id.as_str(),
+                household_id,
+                &regional.system.string(),
+                &regional.rating,
+            ],
+        )?;
+    }
+    return Ok(());
+}
 
 pub struct GameDataBase;
 impl GameDataBase {
-    pub async fn get_game_with_id(id: String) -> Result<Option<Game>, ServiceError> {
+    pub async fn get_game_with_id(
+        id: String,
+        household_id: &str,
+    ) -> Result<Option<Game>, ServiceError> {
         let conn = get_connection()?;
         let res = conn.query_row_and_then(
-            "SELECT id, title, platform, rating, number_of_players FROM games WHERE id=1?",
-            [id],
+            "SELECT id, title, platform, rating, min_players, max_local_players, couch_coop,
+                 online, max_online_players FROM games
+             WHERE id=?1 AND household_id=?2",
+            [id.as_str(), household_id],
             |row| {
                 if let Some(platform) =
                     PlatformType::platform_from_string(&row.get::<usize, String>(2)?)
                 {
-                    if let Some(rating) =
-                        ESRBRating::rating_from_string(&row.get::<usize, String>(3)?)
-                    {
-                        return Ok(Game {
-                            id: row.get::<usize, String>(0)?,
-                            title: row.get::<usize, String>(1)?.to_string(),
-                            platform,
-                            rating,
-                            number_of_players: row.get::<usize, u8>(4)?,
-                        });
-                    }
+                    return Ok(Game {
+                        id: row.get::<usize, String>(0)?,
+                        title: row.get::<usize, String>(1)?.to_string(),
+                        platform,
+                        rating: rating_from_row(row)?,
+                        regional_ratings: vec![],
+                        players: players_from_row(row)?,
+                    });
                 }
                 return Err(rusqlite::Error::FromSqlConversionFailure(
                     1,
@@ -33,31 +119,38 @@ impl GameDataBase {
             },
         );
         let interpreted_res = match res {
-            Ok(game) => Ok(Some(game)),
+            Ok(mut game) => {
+                game.regional_ratings = regional_ratings(&conn, household_id, Some(&game.id))?
+                    .remove(&game.id)
+                    .unwrap_or_default();
+                Ok(Some(game))
+            }
             Err(_) => Err(ServiceError::GameNotFound),
         };
-        return Ok(interpreted_res?);
+        return interpreted_res;
     }
 
-    pub async fn get_games() -> Result<Option<Vec<Game>>, ServiceError> {
+    pub async fn get_games(household_id: &str) -> Result<Option<Vec<Game>>, ServiceError> {
         let conn = get_connection()?;
         let mut statment = conn
-            .prepare("SELECT id, title, platform, rating, number_of_players FROM games")
+            .prepare(
+                "SELECT id, title, platform, rating, min_players, max_local_players, couch_coop,
+                 online, max_online_players FROM games
+                 WHERE household_id=?1",
+            )
             .unwrap();
-        let games_res = statment.query_map([], |row| {
+        let games_res = statment.query_map([household_id], |row| {
             if let Some(platform) =
                 PlatformType::platform_from_string(&row.get::<usize, String>(2)?)
             {
-                if let Some(rating) = ESRBRating::rating_from_string(&row.get::<usize, String>(3)?)
-                {
-                    return Ok(Game {
-                        id: row.get::<usize, String>(0)?,
-                        title: row.get::<usize, String>(1)?,
-                        platform,
-                        rating,
-                        number_of_players: row.get::<usize, u8>(4)?,
-                    });
-                }
+                return Ok(Game {
+                    id: row.get::<usize, String>(0)?,
+                    title: row.get::<usize, String>(1)?,
+                    platform,
+                    rating: rating_from_row(row)?,
+                    regional_ratings: vec![],
+                    players: players_from_row(row)?,
+                });
             }
             return Err(rusqlite::Error::FromSqlConversionFailure(
                 1,
@@ -68,38 +161,201 @@ impl GameDataBase {
         return match games_res {
             Ok(game_map) => {
                 let mut game_list = vec![];
-                game_map.for_each(|game| match game {
-                    Ok(game) => game_list.push(game),
-                    Err(_) => {}
+                game_map.for_each(|game| {
+                    if let Ok(game) = game {
+                        game_list.push(game)
+                    }
                 });
+                let mut ratings = regional_ratings(&conn, household_id, None)?;
+                for game in game_list.iter_mut() {
+                    game.regional_ratings = ratings.remove(&game.id).unwrap_or_default();
+                }
                 return Ok(Some(game_list));
             }
             Err(_) => Err(ServiceError::GameNotFound),
         };
     }
 
-    pub async fn delete_game(id: Option<String>) -> Result<bool, ServiceError> {
-        let conn = get_connection()?;
-        let result = match id {
-            Some(id) => conn.execute("DELETE FROM games WHERE id=?1", [id]),
-            None => conn.execute("DROP TABLE games", []),
+    pub async fn search_games(
+        query: &str,
+        household_id: &str,
+    ) -> Result<Vec<(Game, f32)>, ServiceError> {
+        let games = GameDataBase::get_games(household_id)
+            .await?
+            .unwrap_or_default();
+        let mut matches: Vec<(Game, f32)> = games
+            .into_iter()
+            .map(|game| {
+                let score = title_similarity(query, &game.title);
+                (game, score)
+            })
+            .filter(|(_, score)| *score >= MATCH_THRESHOLD)
+            .collect();
+        matches.sort_by(|left, right| right.1.total_cmp(&left.1));
+        return Ok(matches);
+    }
+
+    pub async fn find_duplicates(
+        new_game: &Game,
+        household_id: &str,
+    ) -> Result<Vec<(Game, f32)>, ServiceError> {
+        // only an exact match on the normalized title blocks an insert,
+        // near misses are left to the lookup and duplicate cluster listings
+        let title = normalize_title(&new_game.title);
+        let games = GameDataBase::get_games(household_id)
+            .await?
+            .unwrap_or_default();
+        return Ok(games
+            .into_iter()
+            .filter(|game| {
+                game.platform == new_game.platform && normalize_title(&game.title) == title
+            })
+            .map(|game| (game, 1.0))
+            .collect());
+    }
+
+    pub async fn get_duplicate_clusters(
+        household_id: &str,
+    ) -> Result<Vec<Vec<Game>>, ServiceError> {
+        let mut remaining = GameDataBase::get_games(household_id)
+            .await?
+            .unwrap_or_default();
+        let mut clusters = vec![];
+        while let Some(first) = remaining.pop() {
+            let (mut cluster, rest): (Vec<Game>, Vec<Game>) =
+                remaining.into_iter().partition(|other| {
+                    other.platform == first.platform
+                        && title_similarity(&first.title, &other.title) >= LIKELY_SAME_THRESHOLD
+                });
+            remaining = rest;
+            if !cluster.is_empty() {
+                cluster.insert(0, first);
+                clusters.push(cluster);
+            }
+        }
+        return Ok(clusters);
+    }
+
+    pub async fn merge_games(
+        request: MergeRequest,
+        household_id: &str,
+    ) -> Result<usize, ServiceError> {
+        if !request.is_valid() {
+            return Err(ServiceError::InvalidMergeRequest);
+        }
+        let mut conn = get_connection()?;
+        let tx = conn.transaction().or(Err(ServiceError::FailedToMerge))?;
+        let platform_of = |id: &str| {
+            return tx
+                .query_row(
+                    "SELECT platform FROM games WHERE id=?1 AND household_id=?2",
+                    [id, household_id],
+                    |row| row.get::<usize, String>(0),
+                )
+                .optional()
+                .or(Err(ServiceError::FailedToMerge))?
+                .ok_or(ServiceError::GameNotFound);
         };
+        let survivor_platform = platform_of(&request.survivor_id)?;
+        for duplicate_id in &request.duplicate_ids {
+            if platform_of(duplicate_id)? != survivor_platform {
+                return Err(ServiceError::MismatchedMerge);
+            }
+            reassign_media_references(
+                &tx,
+                MediaKind::Game,
+                household_id,
+                &request.survivor_id,
+                duplicate_id,
+            )
+            .or(Err(ServiceError::FailedToMerge))?;
+            // the survivor keeps its own rating wherever both were rated by the same board
+            tx.execute(
+                "UPDATE OR IGNORE game_ratings SET game_id=?1 WHERE game_id=?2 AND household_id=?3",
+                [request.survivor_id.as_str(), duplicate_id, household_id],
+            )
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM game_ratings WHERE game_id=?1 AND household_id=?2",
+                    [duplicate_id.as_str(), household_id],
+                )
+            })
+            .or(Err(ServiceError::FailedToMerge))?;
+            let removed = tx
+                .execute(
+                    "DELETE FROM games WHERE id=?1 AND household_id=?2",
+                    [duplicate_id.as_str(), household_id],
+                )
+                .or(Err(ServiceError::FailedToMerge))?;
+            if removed == 0 {
+                return Err(ServiceError::GameNotFound);
+            }
+        }
+        tx.commit().or(Err(ServiceError::FailedToMerge))?;
+        return Ok(request.duplicate_ids.len());
+    }
+
+    pub async fn delete_game(id: Option<String>, household_id: &str) -> Result<bool, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let result = remove_media_references(&tx, MediaKind::Game, household_id, id.as_deref())
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM game_ratings WHERE household_id=?1 AND (?2 IS NULL OR game_id=?2)",
+                    params![household_id, id],
+                )
+            })
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM games WHERE household_id=?1 AND (?2 IS NULL OR id=?2)",
+                    params![household_id, id],
+                )
+            })
+            .and_then(|_| tx.commit());
         return match result {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         };
     }
 
-    pub async fn update_game(updated_game: Game) -> Result<bool, ServiceError> {
-        let conn = get_connection()?;
-        let statement_result = conn.execute("UPDATE games 
-                                             SET title=?1, platform=?2, rating=?3, number_of_players=?4 
-                                             WHERE id=?5", 
-                                             [updated_game.title, updated_game.platform.string(),
-                                             updated_game.number_of_players.to_string()]);
+    pub async fn update_game(updated_game: Game, household_id: &str) -> Result<bool, ServiceError> {
+        if !updated_game.players.is_valid() {
+            return Err(ServiceError::InvalidPlayerCounts);
+        }
+        if !updated_game.has_valid_ratings() {
+            return Err(ServiceError::InvalidRating);
+        }
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::FailedToUpdateGame))?;
+        let players = updated_game.players;
+        let statement_result = tx.execute(
+            "UPDATE games SET title=?1, platform=?2, rating=?3, min_players=?4,
+             max_local_players=?5, couch_coop=?6, online=?7, max_online_players=?8
+             WHERE id=?9 AND household_id=?10",
+            params![
+                updated_game.title,
+                updated_game.platform.string(),
+                updated_game.rating.map(|rating| rating.string()),
+                players.min_players,
+                players.max_local_players,
+                players.couch_coop,
+                players.online,
+                players.max_online_players,
+                updated_game.id,
+                household_id,
+            ],
+        );
         return match statement_result {
             Ok(rows_updated) => {
                 if rows_updated > 0 {
+                    // the board ratings and the rest of the row change together or not at all
+                    save_regional_ratings(&tx, household_id, &updated_game)
+                        .and_then(|_| tx.commit())
+                        .or(Err(ServiceError::FailedToUpdateGame))?;
                     Ok(true)
                 } else {
                     Ok(false)
@@ -109,21 +365,37 @@ impl GameDataBase {
         };
     }
 
-    pub async fn insert_game(new_game: Game) -> Result<bool, ServiceError> {
-        let conn = get_connection()?;
-        let statement_result = conn.execute(
-            "INSERT INTO games (id, title, platform, rating, number_of_players) 
-                                             VALUES (?1,?2,?3,?4,?5);",
-            [
+    pub async fn insert_game(new_game: Game, household_id: &str) -> Result<bool, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let players = new_game.players;
+        let statement_result = tx.execute(
+            "INSERT INTO games (id, title, platform, rating, min_players, max_local_players,
+             couch_coop, online, max_online_players, household_id)
+             VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10);",
+            params![
                 new_game.id,
                 new_game.title,
                 new_game.platform.string(),
-                new_game.rating.string(),
-                new_game.number_of_players.to_string(),
+                new_game.rating.map(|rating| rating.string()),
+                players.min_players,
+                players.max_local_players,
+                players.couch_coop,
+                players.online,
+                players.max_online_players,
+                household_id,
             ],
         );
         return match statement_result {
-            Ok(_) => Ok(true),
+            Ok(_) => {
+                // a game whose ratings didn't save isn't kept either
+                save_regional_ratings(&tx, household_id, &new_game)
+                    .and_then(|_| tx.commit())
+                    .or(Err(ServiceError::ConnectionFailure))?;
+                Ok(true)
+            }
             Err(_) => Ok(false),
         };
     }
@@ -0,0 +1,234 @@
+use crate::data_models::copies::{Condition, MediaCopy};
+use crate::data_models::game::PlatformType;
+use crate::data_models::hardware::{
+    is_region_locked_game, is_region_locked_movie, CompatibilityReport, Hardware,
+    HardwareInventory, HardwareKind, PlayableQuery, VideoStandard,
+};
+use crate::data_models::lookup::MediaKind;
+use crate::data_models::movie::MotionPictureFormat;
+use crate::database_services::database_utilities::get_connection;
+use crate::database_services::game_database::GameDataBase;
+use crate::database_services::movie_database::MovieDataBase;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::{params, Row};
+use uuid::Uuid;
+
+fn hardware_from_row(row: &Row) -> Result<Hardware, rusqlite::Error> {
+    return Ok(Hardware {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: HardwareKind::from_string(&row.get::<usize, String>(2)?)
+            .unwrap_or(HardwareKind::Console),
+        platform: row
+            .get::<usize, Option<String>>(3)?
+            .and_then(|platform| PlatformType::platform_from_string(&platform)),
+        format: row
+            .get::<usize, Option<String>>(4)?
+            .and_then(|format| MotionPictureFormat::from_string(&format)),
+        video_standard: row
+            .get::<usize, Option<String>>(5)?
+            .and_then(|standard| VideoStandard::from_string(&standard)),
+        region_code: row.get(6)?,
+        region_free: row.get(7)?,
+        count: row.get(8)?,
+        condition: row
+            .get::<usize, Option<String>>(9)?
+            .and_then(|condition| Condition::from_string(&condition)),
+        broken: row.get(10)?,
+        revision: row.get(11)?,
+    });
+}
+
+pub struct HardwareDataBase;
+impl HardwareDataBase {
+    pub async fn get_hardware(household_id: &str) -> Result<Vec<Hardware>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, name, kind, platform, format, video_standard, region_code, region_free,
+                 count, condition, broken, revision FROM hardware WHERE household_id=?1 ORDER BY name COLLATE NOCASE",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let hardware = statement
+            .query_map([household_id], hardware_from_row)
+            .or(Err(ServiceError::HardwareNotFound))?;
+        return Ok(hardware.filter_map(|item| item.ok()).collect());
+    }
+
+    pub async fn add_hardware(
+        household_id: &str,
+        mut hardware: Hardware,
+    ) -> Result<Hardware, ServiceError> {
+        if !hardware.is_valid() {
+            return Err(ServiceError::InvalidHardware);
+        }
+        hardware.id = Uuid::new_v4().to_string();
+        let conn = get_connection()?;
+        conn.execute(
+            "INSERT INTO hardware (id, household_id, name, kind, platform, format, video_standard,
+             region_code, region_free, count, condition, broken, revision)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                hardware.id,
+                household_id,
+                hardware.name,
+                hardware.kind.string(),
+                hardware.platform.map(|platform| platform.string()),
+                hardware.format.as_ref().map(|format| format.string()),
+                hardware.video_standard.map(|standard| standard.string()),
+                hardware.region_code,
+                hardware.region_free,
+                hardware.count,
+                hardware.condition.map(|condition| condition.string()),
+                hardware.broken,
+                hardware.revision
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(hardware);
+    }
+
+    pub async fn update_hardware(
+        household_id: &str,
+        hardware_id: &str,
+        hardware: Hardware,
+    ) -> Result<bool, ServiceError> {
+        if !hardware.is_valid() {
+            return Err(ServiceError::InvalidHardware);
+        }
+        let conn = get_connection()?;
+        let updated = conn
+            .execute(
+                "UPDATE hardware SET name=?1, kind=?2, platform=?3, format=?4, video_standard=?5,
+                 region_code=?6, region_free=?7, count=?8, condition=?9, broken=?10,
+                 revision=?11 WHERE id=?12 AND household_id=?13",
+                params![
+                    hardware.name,
+                    hardware.kind.string(),
+                    hardware.platform.map(|platform| platform.string()),
+                    hardware.format.as_ref().map(|format| format.string()),
+                    hardware.video_standard.map(|standard| standard.string()),
+                    hardware.region_code,
+                    hardware.region_free,
+                    hardware.count,
+                    hardware.condition.map(|condition| condition.string()),
+                    hardware.broken,
+                    hardware.revision,
+                    hardware_id,
+                    household_id
+                ],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if updated == 0 {
+            return Err(ServiceError::HardwareNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn delete_hardware(
+        household_id: &str,
+        hardware_id: &str,
+    ) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let removed = conn
+            .execute(
+                "DELETE FROM hardware WHERE id=?1 AND household_id=?2",
+                [hardware_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if removed == 0 {
+            return Err(ServiceError::HardwareNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn get_inventory(household_id: &str) -> Result<HardwareInventory, ServiceError> {
+        let hardware = HardwareDataBase::get_hardware(household_id).await?;
+        return Ok(HardwareInventory::new(hardware));
+    }
+
+    // The console a game would be played on: the one picked by id, or every working console
+    // for the platform. With none tracked the platform alone decides.
+    pub async fn get_consoles_for(
+        household_id: &str,
+        query: &PlayableQuery,
+    ) -> Result<(PlatformType, Vec<Hardware>), ServiceError> {
+        let hardware = HardwareDataBase::get_hardware(household_id).await?;
+        if let Some(hardware_id) = &query.hardware_id {
+            let console = hardware
+                .into_iter()
+                .find(|item| &item.id == hardware_id && item.kind == HardwareKind::Console)
+                .ok_or(ServiceError::HardwareNotFound)?;
+            let platform = console.platform.ok_or(ServiceError::HardwareNotFound)?;
+            return Ok((platform, vec![console]));
+        }
+        let platform = query.platform.ok_or(ServiceError::InvalidPlayableQuery)?;
+        let consoles = hardware
+            .into_iter()
+            .filter(|item| {
+                item.kind == HardwareKind::Console
+                    && item.platform == Some(platform)
+                    && !item.broken
+            })
+            .collect();
+        return Ok((platform, consoles));
+    }
+
+    // Puts a warning on a copy that nothing in the house can play because of its region
+    pub async fn flag_unplayable(
+        household_id: &str,
+        copy: &mut MediaCopy,
+    ) -> Result<(), ServiceError> {
+        let report = HardwareDataBase::check_copy(household_id, copy).await?;
+        // with nothing tracked for the format there's no player to blame the region on
+        if report.region_locked && report.has_hardware && !report.playable {
+            copy.warning = Some("Nothing in the house can play this region".to_string());
+        }
+        return Ok(());
+    }
+
+    // Works out which of the household's consoles and players will take this copy
+    pub async fn check_copy(
+        household_id: &str,
+        copy: &MediaCopy,
+    ) -> Result<CompatibilityReport, ServiceError> {
+        let hardware = HardwareDataBase::get_hardware(household_id).await?;
+        let (region_locked, has_hardware, compatible_hardware) = match copy.media_kind {
+            MediaKind::Game => {
+                let game = GameDataBase::get_game_with_id(copy.media_id.clone(), household_id)
+                    .await?
+                    .ok_or(ServiceError::GameNotFound)?;
+                (
+                    is_region_locked_game(&game, copy),
+                    hardware
+                        .iter()
+                        .any(|item| item.runs_platform(game.platform)),
+                    hardware
+                        .into_iter()
+                        .filter(|item| item.can_play_game(&game, copy))
+                        .collect::<Vec<Hardware>>(),
+                )
+            }
+            MediaKind::Movie => {
+                let movie = MovieDataBase::get_movie_with_id(copy.media_id.clone(), household_id)
+                    .await?
+                    .ok_or(ServiceError::MovieNotFound)?;
+                (
+                    is_region_locked_movie(&movie, copy),
+                    hardware.iter().any(|item| item.reads_format(&movie.format)),
+                    hardware
+                        .into_iter()
+                        .filter(|item| item.can_play_movie(&movie, copy))
+                        .collect::<Vec<Hardware>>(),
+                )
+            }
+        };
+        return Ok(CompatibilityReport {
+            copy_id: copy.id.clone(),
+            region_locked,
+            has_hardware,
+            playable: !compatible_hardware.is_empty(),
+            compatible_hardware,
+        });
+    }
+}
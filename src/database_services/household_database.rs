@@ -0,0 +1,166 @@
+use crate::data_models::auth_models::{HouseholdInvite, HouseholdMember, Role};
+use crate::database_services::database_utilities::get_connection;
+use crate::errors::servive_errors::ServiceError;
+use chrono::TimeDelta;
+use rusqlite::{Connection, OptionalExtension};
+use uuid::Uuid;
+
+const INVITE_LIFETIME_DAYS: i64 = 7;
+const HOUSEHOLD_DATA_TABLES: &[&str] = &[
+    "games",
+    "movies",
+    "copies",
+    "hardware",
+    "locations",
+    "contacts",
+    "loans",
+    "wishlist_items",
+];
+
+pub struct HouseholdDataBase;
+impl HouseholdDataBase {
+    pub async fn get_members(household_id: &str) -> Result<Vec<HouseholdMember>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare("SELECT id, username, role FROM users WHERE household_id=?1")
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let members = statement
+            .query_map([household_id], |row| {
+                return Ok(HouseholdMember {
+                    user_id: row.get(0)?,
+                    username: row.get(1)?,
+                    role: Role::from_string(&row.get::<usize, String>(2)?).unwrap_or(Role::Viewer),
+                });
+            })
+            .or(Err(ServiceError::MemberNotFound))?;
+        return Ok(members.filter_map(|member| member.ok()).collect());
+    }
+
+    pub async fn set_member_role(
+        household_id: &str,
+        user_id: &str,
+        role: Role,
+    ) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let current_role = conn
+            .query_row(
+                "SELECT role FROM users WHERE id=?1 AND household_id=?2",
+                [user_id, household_id],
+                |row| row.get::<usize, String>(0),
+            )
+            .or(Err(ServiceError::MemberNotFound))?;
+        if current_role == Role::Owner.string()
+            && role != Role::Owner
+            && HouseholdDataBase::owner_count(&conn, household_id)? <= 1
+        {
+            return Err(ServiceError::LastOwner);
+        }
+        let updated = conn
+            .execute(
+                "UPDATE users SET role=?1 WHERE id=?2 AND household_id=?3",
+                [role.string().as_str(), user_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(updated > 0);
+    }
+
+    pub async fn create_invite(
+        household_id: &str,
+        role: Role,
+    ) -> Result<HouseholdInvite, ServiceError> {
+        let conn = get_connection()?;
+        let code = Uuid::new_v4().to_string();
+        let lifetime = TimeDelta::days(INVITE_LIFETIME_DAYS).num_seconds();
+        let expiry = conn
+            .query_row("SELECT get_expiry(?1)", [lifetime], |row| {
+                row.get::<usize, String>(0)
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        conn.execute(
+            "INSERT INTO household_invites (code, household_id, role, expiry) VALUES (?1, ?2, ?3, ?4)",
+            [code.as_str(), household_id, &role.string(), &expiry],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(HouseholdInvite { code, role, expiry });
+    }
+
+    pub async fn join_household(
+        user_id: &str,
+        current_household_id: &str,
+        code: &str,
+    ) -> Result<bool, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let invite = tx
+            .query_row(
+                "SELECT household_id, role FROM household_invites
+                 WHERE code=?1 AND NOT is_expired(expiry)",
+                [code],
+                |row| Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?)),
+            )
+            .optional()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let (household_id, role) = invite.ok_or(ServiceError::InvalidInvite)?;
+        // leaving must not strand the old household's other members without an owner
+        let others_left = tx
+            .query_row(
+                "SELECT COUNT(*) FROM users WHERE household_id=?1 AND id!=?2",
+                [current_household_id, user_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let is_owner = tx
+            .query_row("SELECT role FROM users WHERE id=?1", [user_id], |row| {
+                row.get::<usize, String>(0)
+            })
+            .or(Err(ServiceError::MemberNotFound))?
+            == Role::Owner.string();
+        if others_left > 0
+            && is_owner
+            && HouseholdDataBase::owner_count(&tx, current_household_id)? <= 1
+        {
+            return Err(ServiceError::LastOwner);
+        }
+        // nobody would be left to reach the collection if its last member walked away
+        if others_left == 0 && HouseholdDataBase::holds_data(&tx, current_household_id)? {
+            return Err(ServiceError::HouseholdNotEmpty);
+        }
+        tx.execute(
+            "UPDATE users SET household_id=?1, role=?2 WHERE id=?3",
+            [household_id.as_str(), &role, user_id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        tx.execute("DELETE FROM household_invites WHERE code=?1", [code])
+            .or(Err(ServiceError::ConnectionFailure))?;
+        tx.commit().or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(true);
+    }
+
+    fn holds_data(conn: &Connection, household_id: &str) -> Result<bool, ServiceError> {
+        for table in HOUSEHOLD_DATA_TABLES {
+            let rows = conn
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {table} WHERE household_id=?1"),
+                    [household_id],
+                    |row| row.get::<usize, usize>(0),
+                )
+                .or(Err(ServiceError::ConnectionFailure))?;
+            if rows > 0 {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+
+    fn owner_count(conn: &Connection, household_id: &str) -> Result<usize, ServiceError> {
+        return conn
+            .query_row(
+                "SELECT COUNT(*) FROM users WHERE household_id=?1 AND role=?2",
+                [household_id, &Role::Owner.string()],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure));
+    }
+}
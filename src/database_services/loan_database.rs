@@ -0,0 +1,235 @@
+use crate::data_models::loans::{CheckinRequest, CheckoutRequest, Loan};
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::contact_database::ContactDataBase;
+use crate::database_services::database_utilities::{get_connection, today, validate_date};
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::{params, params_from_iter, ErrorCode, OptionalExtension, Row};
+use uuid::Uuid;
+
+const LOAN_COLUMNS: &str =
+    "id, media_kind, media_id, borrower, contact_id, lent_on, due_on, returned_on, copy_id";
+
+fn loan_from_row(row: &Row) -> Result<Loan, rusqlite::Error> {
+    let kind: String = row.get(1)?;
+    return Ok(Loan {
+        id: row.get(0)?,
+        media_kind: MediaKind::from_string(&kind).unwrap_or(MediaKind::Game),
+        media_id: row.get(2)?,
+        copy_id: row.get(8)?,
+        borrower: row.get(3)?,
+        contact_id: row.get(4)?,
+        lent_on: row.get(5)?,
+        due_on: row.get(6)?,
+        returned_on: row.get(7)?,
+    });
+}
+
+pub struct LoanDataBase;
+impl LoanDataBase {
+    pub async fn check_out(
+        household_id: &str,
+        request: CheckoutRequest,
+    ) -> Result<Loan, ServiceError> {
+        let lent_on = validate_date(request.lent_on)?.unwrap_or(today());
+        let due_on = validate_date(request.due_on)?;
+        // both dates are normalised to year-month-day so they compare as strings
+        if due_on.as_ref().is_some_and(|due_on| due_on < &lent_on) {
+            return Err(ServiceError::DueBeforeLent);
+        }
+        let contact = match &request.contact_id {
+            Some(contact_id) => Some(ContactDataBase::get_contact(household_id, contact_id).await?),
+            None => None,
+        };
+        let borrower = match (request.borrower, contact) {
+            (Some(name), _) if !name.trim().is_empty() => name,
+            (_, Some(contact)) => contact.name,
+            _ => return Err(ServiceError::MissingBorrower),
+        };
+        let conn = get_connection()?;
+        let table = match request.media_kind {
+            MediaKind::Game => "games",
+            MediaKind::Movie => "movies",
+        };
+        let exists = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {table} WHERE id=?1 AND household_id=?2"),
+                [request.media_id.as_str(), household_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if exists == 0 {
+            return Err(match request.media_kind {
+                MediaKind::Game => ServiceError::GameNotFound,
+                MediaKind::Movie => ServiceError::MovieNotFound,
+            });
+        }
+        let mut statement = conn
+            .prepare(
+                "SELECT id FROM copies WHERE household_id=?1 AND media_kind=?2 AND media_id=?3
+                 ORDER BY rowid",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let copy_ids: Vec<String> = statement
+            .query_map(
+                [
+                    household_id,
+                    &request.media_kind.string(),
+                    &request.media_id,
+                ],
+                |row| row.get::<usize, String>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?
+            .filter_map(|copy_id| copy_id.ok())
+            .collect();
+        let copy_id = match request.copy_id {
+            Some(copy_id) if copy_ids.contains(&copy_id) => {
+                if LoanDataBase::current_loan(household_id, &copy_id)
+                    .await?
+                    .is_some()
+                {
+                    return Err(ServiceError::AlreadyOnLoan);
+                }
+                copy_id
+            }
+            Some(_) => return Err(ServiceError::CopyNotFound),
+            None => {
+                if copy_ids.is_empty() {
+                    return Err(ServiceError::CopyNotFound);
+                }
+                let mut on_shelf = None;
+                for copy_id in copy_ids {
+                    if LoanDataBase::current_loan(household_id, &copy_id)
+                        .await?
+                        .is_none()
+                    {
+                        on_shelf = Some(copy_id);
+                        break;
+                    }
+                }
+                on_shelf.ok_or(ServiceError::AlreadyOnLoan)?
+            }
+        };
+        let loan = Loan {
+            id: Uuid::new_v4().to_string(),
+            media_kind: request.media_kind,
+            media_id: request.media_id,
+            copy_id: Some(copy_id),
+            borrower,
+            contact_id: request.contact_id,
+            lent_on,
+            due_on,
+            returned_on: None,
+        };
+        conn.execute(
+            "INSERT INTO loans
+             (id, household_id, media_kind, media_id, borrower, contact_id, lent_on, due_on,
+             copy_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                loan.id,
+                household_id,
+                loan.media_kind.string(),
+                loan.media_id,
+                loan.borrower,
+                loan.contact_id,
+                loan.lent_on,
+                loan.due_on,
+                loan.copy_id
+            ],
+        )
+        .map_err(|error| match error.sqlite_error_code() {
+            // another checkout of the same copy got in first
+            Some(ErrorCode::ConstraintViolation) => ServiceError::AlreadyOnLoan,
+            _ => ServiceError::ConnectionFailure,
+        })?;
+        return Ok(loan);
+    }
+
+    pub async fn check_in(
+        household_id: &str,
+        loan_id: &str,
+        request: CheckinRequest,
+    ) -> Result<bool, ServiceError> {
+        let returned_on = validate_date(request.returned_on)?.unwrap_or(today());
+        let conn = get_connection()?;
+        let lent_on = conn
+            .query_row(
+                "SELECT lent_on FROM loans WHERE id=?1 AND household_id=?2 AND returned_on IS NULL",
+                [loan_id, household_id],
+                |row| row.get::<usize, String>(0),
+            )
+            .optional()
+            .or(Err(ServiceError::ConnectionFailure))?
+            .ok_or(ServiceError::LoanNotFound)?;
+        if returned_on < lent_on {
+            return Err(ServiceError::ReturnedBeforeLent);
+        }
+        let updated = conn
+            .execute(
+                "UPDATE loans SET returned_on=?1
+                 WHERE id=?2 AND household_id=?3 AND returned_on IS NULL",
+                [returned_on.as_str(), loan_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if updated == 0 {
+            return Err(ServiceError::LoanNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn current_loan(
+        household_id: &str,
+        copy_id: &str,
+    ) -> Result<Option<Loan>, ServiceError> {
+        let conn = get_connection()?;
+        return conn
+            .query_row(
+                &format!(
+                    "SELECT {LOAN_COLUMNS} FROM loans
+                     WHERE household_id=?1 AND copy_id=?2 AND returned_on IS NULL"
+                ),
+                [household_id, copy_id],
+                loan_from_row,
+            )
+            .optional()
+            .or(Err(ServiceError::ConnectionFailure));
+    }
+
+    pub async fn get_active_loans(household_id: &str) -> Result<Vec<Loan>, ServiceError> {
+        return LoanDataBase::query_loans(
+            "returned_on IS NULL ORDER BY due_on IS NULL, due_on",
+            &[household_id],
+        );
+    }
+
+    pub async fn get_overdue_loans(household_id: &str) -> Result<Vec<Loan>, ServiceError> {
+        return LoanDataBase::query_loans(
+            "returned_on IS NULL AND due_on < ?2 ORDER BY due_on",
+            &[household_id, &today()],
+        );
+    }
+
+    pub async fn get_loans_for_contact(
+        household_id: &str,
+        contact_id: &str,
+    ) -> Result<Vec<Loan>, ServiceError> {
+        return LoanDataBase::query_loans(
+            "contact_id=?2 ORDER BY lent_on DESC",
+            &[household_id, contact_id],
+        );
+    }
+
+    // ?1 is always the household, any extra values in the condition start at ?2
+    fn query_loans(condition: &str, values: &[&str]) -> Result<Vec<Loan>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {LOAN_COLUMNS} FROM loans WHERE household_id=?1 AND {condition}"
+            ))
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let loans = statement
+            .query_map(params_from_iter(values), loan_from_row)
+            .or(Err(ServiceError::LoanNotFound))?;
+        return Ok(loans.filter_map(|loan| loan.ok()).collect());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::data_models::locations::{
+    Location, LocationAssignment, NewLocation, StoredItem, PATH_SEPARATOR,
+};
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::database_utilities::get_connection;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::params;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Every location in the household keyed by id, with paths worked out from the parents
+pub struct LocationTree {
+    locations: HashMap<String, Location>,
+}
+
+impl LocationTree {
+    pub async fn for_household(household_id: &str) -> Result<Self, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare("SELECT id, parent_id, name FROM locations WHERE household_id=?1")
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let rows = statement
+            .query_map([household_id], |row| {
+                return Ok(Location {
+                    id: row.get(0)?,
+                    parent_id: row.get(1)?,
+                    name: row.get(2)?,
+                    path: String::new(),
+                });
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let mut locations: HashMap<String, Location> = rows
+            .filter_map(|row| row.ok())
+            .map(|location| (location.id.clone(), location))
+            .collect();
+        let paths: Vec<(String, String)> = locations
+            .keys()
+            .map(|id| (id.clone(), LocationTree::build_path(&locations, id)))
+            .collect();
+        for (id, path) in paths {
+            if let Some(location) = locations.get_mut(&id) {
+                location.path = path;
+            }
+        }
+        return Ok(LocationTree { locations });
+    }
+
+    fn build_path(locations: &HashMap<String, Location>, id: &str) -> String {
+        let mut names = vec![];
+        let mut current = locations.get(id);
+        // the length check guards against a cycle that slipped into the table
+        while let Some(location) = current {
+            if names.len() > locations.len() {
+                break;
+            }
+            names.push(location.name.as_str());
+            current = location
+                .parent_id
+                .as_ref()
+                .and_then(|parent_id| locations.get(parent_id));
+        }
+        names.reverse();
+        return names.join(PATH_SEPARATOR);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Location> {
+        return self.locations.get(id);
+    }
+
+    pub fn path(&self, id: &str) -> Option<String> {
+        return self.get(id).map(|location| location.path.clone());
+    }
+
+    pub fn all(&self) -> Vec<Location> {
+        let mut locations: Vec<Location> = self.locations.values().cloned().collect();
+        locations.sort_by(|left, right| left.path.cmp(&right.path));
+        return locations;
+    }
+
+    fn is_descendant(&self, id: &str, ancestor_id: &str) -> bool {
+        let mut current = Some(id.to_string());
+        let mut steps = 0;
+        while let Some(location_id) = current {
+            if location_id == ancestor_id {
+                return true;
+            }
+            steps += 1;
+            if steps > self.locations.len() {
+                return true;
+            }
+            current = self
+                .locations
+                .get(&location_id)
+                .and_then(|location| location.parent_id.clone());
+        }
+        return false;
+    }
+}
+
+pub struct LocationDataBase;
+impl LocationDataBase {
+    pub async fn add_location(
+        household_id: &str,
+        request: NewLocation,
+    ) -> Result<Location, ServiceError> {
+        if request.name.trim().is_empty() {
+            return Err(ServiceError::InvalidLocation);
+        }
+        if let Some(parent_id) = &request.parent_id {
+            LocationTree::for_household(household_id)
+                .await?
+                .get(parent_id)
+                .ok_or(ServiceError::LocationNotFound)?;
+        }
+        let id = Uuid::new_v4().to_string();
+        let conn = get_connection()?;
+        conn.execute(
+            "INSERT INTO locations (id, household_id, parent_id, name) VALUES (?1, ?2, ?3, ?4)",
+            params![id, household_id, request.parent_id, request.name.trim()],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return LocationTree::for_household(household_id)
+            .await?
+            .get(&id)
+            .cloned()
+            .ok_or(ServiceError::LocationNotFound);
+    }
+
+    pub async fn update_location(
+        household_id: &str,
+        location_id: &str,
+        request: NewLocation,
+    ) -> Result<bool, ServiceError> {
+        if request.name.trim().is_empty() {
+            return Err(ServiceError::InvalidLocation);
+        }
+        let tree = LocationTree::for_household(household_id).await?;
+        tree.get(location_id)
+            .ok_or(ServiceError::LocationNotFound)?;
+        if let Some(parent_id) = &request.parent_id {
+            tree.get(parent_id).ok_or(ServiceError::LocationNotFound)?;
+            // a shelf can't be moved inside one of its own bins
+            if tree.is_descendant(parent_id, location_id) {
+                return Err(ServiceError::InvalidLocation);
+            }
+        }
+        let conn = get_connection()?;
+        conn.execute(
+            "UPDATE locations SET name=?1, parent_id=?2 WHERE id=?3 AND household_id=?4",
+            params![
+                request.name.trim(),
+                request.parent_id,
+                location_id,
+                household_id
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(true);
+    }
+
+    pub async fn delete_location(
+        household_id: &str,
+        location_id: &str,
+    ) -> Result<bool, ServiceError> {
+        LocationTree::for_household(household_id)
+            .await?
+            .get(location_id)
+            .ok_or(ServiceError::LocationNotFound)?;
+        let conn = get_connection()?;
+        let in_use = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM locations WHERE parent_id=?1)
+                      + (SELECT COUNT(*) FROM copies WHERE location_id=?1)",
+                [location_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if in_use > 0 {
+            return Err(ServiceError::LocationNotEmpty);
+        }
+        conn.execute(
+            "DELETE FROM locations WHERE id=?1 AND household_id=?2",
+            [location_id, household_id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(true);
+    }
+
+    pub async fn assign_location(
+        household_id: &str,
+        assignment: LocationAssignment,
+    ) -> Result<bool, ServiceError> {
+        if let Some(location_id) = &assignment.location_id {
+            LocationTree::for_household(household_id)
+                .await?
+                .get(location_id)
+                .ok_or(ServiceError::LocationNotFound)?;
+        }
+        let conn = get_connection()?;
+        let updated = conn
+            .execute(
+                "UPDATE copies SET location_id=?1 WHERE id=?2 AND household_id=?3",
+                params![assignment.location_id, assignment.copy_id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if updated == 0 {
+            return Err(ServiceError::CopyNotFound);
+        }
+        return Ok(true);
+    }
+
+    // Where the copies of each game and movie in the household are kept, keyed by kind and id
+    pub async fn get_item_locations(
+        household_id: &str,
+    ) -> Result<HashMap<(MediaKind, String), Vec<String>>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT media_kind, media_id, location_id FROM copies
+                 WHERE household_id=?1 AND location_id IS NOT NULL",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let rows = statement
+            .query_map([household_id], |row| {
+                return Ok((
+                    row.get::<usize, String>(0)?,
+                    row.get::<usize, String>(1)?,
+                    row.get::<usize, String>(2)?,
+                ));
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let mut locations: HashMap<(MediaKind, String), Vec<String>> = HashMap::new();
+        for (kind, id, location_id) in rows.filter_map(|row| row.ok()) {
+            if let Some(kind) = MediaKind::from_string(&kind) {
+                locations.entry((kind, id)).or_default().push(location_id);
+            }
+        }
+        return Ok(locations);
+    }
+
+    pub async fn get_contents(
+        household_id: &str,
+        location_id: &str,
+    ) -> Result<Vec<StoredItem>, ServiceError> {
+        let tree = LocationTree::for_household(household_id).await?;
+        tree.get(location_id)
+            .ok_or(ServiceError::LocationNotFound)?;
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "WITH RECURSIVE subtree(id) AS (
+                    SELECT id FROM locations WHERE id=?1 AND household_id=?2
+                    UNION
+                    SELECT locations.id FROM locations
+                    INNER JOIN subtree ON locations.parent_id = subtree.id
+                 )
+                 SELECT copies.media_kind, copies.media_id, copies.id,
+                     COALESCE(games.title, movies.title), copies.location_id
+                 FROM copies
+                 LEFT JOIN games ON copies.media_kind='Game' AND games.id=copies.media_id
+                 LEFT JOIN movies ON copies.media_kind='Movie' AND movies.id=copies.media_id
+                 WHERE copies.household_id=?2 AND copies.location_id IN subtree
+                 ORDER BY 4 COLLATE NOCASE",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let rows = statement
+            .query_map([location_id, household_id], |row| {
+                return Ok((
+                    row.get::<usize, String>(0)?,
+                    row.get::<usize, String>(1)?,
+                    row.get::<usize, String>(2)?,
+                    row.get::<usize, String>(3)?,
+                    row.get::<usize, String>(4)?,
+                ));
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(rows
+            .filter_map(|row| row.ok())
+            .filter_map(|(kind, id, copy_id, title, location_id)| {
+                Some(StoredItem {
+                    kind: MediaKind::from_string(&kind)?,
+                    id,
+                    copy_id,
+                    title,
+                    location: tree.path(&location_id).unwrap_or_default(),
+                    location_id,
+                })
+            })
+            .collect());
+    }
+}
@@ -1,19 +1,32 @@
+use crate::data_models::lookup::{MediaKind, MergeRequest};
 use crate::data_models::movie::{MPAARating, MotionPictureFormat, Movie};
-use crate::database_services::database_utilities::get_connection;
+use crate::database_services::database_utilities::{
+    get_connection, reassign_media_references, remove_media_references,
+};
+use crate::database_services::title_matching::{
+    normalize_title, title_similarity, LIKELY_SAME_THRESHOLD, MATCH_THRESHOLD,
+};
 use crate::errors::servive_errors::ServiceError;
-use std::usize;
+use rusqlite::params;
+use rusqlite::OptionalExtension;
 
 pub struct MovieDataBase;
 impl MovieDataBase {
-    pub async fn new_movie_with(new_movie: Movie) -> Result<bool, ServiceError> {
+    pub async fn new_movie_with(
+        new_movie: Movie,
+        household_id: &str,
+    ) -> Result<bool, ServiceError> {
         let conn = get_connection()?;
         let res = conn.execute(
-            "INSERT INTO(id, title, format, rating) VALUES( 1?, 2?, 3?, 4?)",
-            [
+            "INSERT INTO movies (id, title, format, rating, runtime_minutes, household_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
                 new_movie.id,
                 new_movie.title,
                 new_movie.format.string(),
                 new_movie.rating.string(),
+                new_movie.runtime_minutes,
+                household_id,
             ],
         );
         return match res {
@@ -28,11 +41,14 @@ impl MovieDataBase {
         };
     }
 
-    pub async fn get_movie_with_id(id: String) -> Result<Option<Movie>, ServiceError> {
+    pub async fn get_movie_with_id(
+        id: String,
+        household_id: &str,
+    ) -> Result<Option<Movie>, ServiceError> {
         let conn = get_connection()?;
         let res = conn.query_row_and_then(
-            "SELECT id, title, format, rating FROM movies WHERE id=1?",
-            [id],
+            "SELECT id, title, format, rating, runtime_minutes FROM movies WHERE id=?1 AND household_id=?2",
+            [id.as_str(), household_id],
             |row| {
                 if let Some(format) =
                     MotionPictureFormat::from_string(&row.get::<usize, String>(2)?)
@@ -43,6 +59,7 @@ impl MovieDataBase {
                             title: row.get::<usize, String>(1)?,
                             format,
                             rating,
+                            runtime_minutes: row.get::<usize, Option<u32>>(4)?,
                         });
                     }
                 }
@@ -59,12 +76,12 @@ impl MovieDataBase {
         };
     }
 
-    pub async fn get_all_movies() -> Result<Option<Vec<Movie>>, ServiceError> {
+    pub async fn get_all_movies(household_id: &str) -> Result<Option<Vec<Movie>>, ServiceError> {
         let conn = get_connection()?;
         let mut stmnt = conn
-            .prepare("SELECT id, title, format, rating FROM movies")
+            .prepare("SELECT id, title, format, rating, runtime_minutes FROM movies WHERE household_id=?1")
             .unwrap();
-        let res = stmnt.query_map([], |row| {
+        let res = stmnt.query_map([household_id], |row| {
             if let Some(format) = MotionPictureFormat::from_string(&row.get::<usize, String>(2)?) {
                 if let Some(rating) = MPAARating::from_string(&row.get::<usize, String>(3)?) {
                     return Ok(Movie {
@@ -72,6 +89,7 @@ impl MovieDataBase {
                         title: row.get::<usize, String>(1)?,
                         format,
                         rating,
+                        runtime_minutes: row.get::<usize, Option<u32>>(4)?,
                     });
                 }
             }
@@ -84,9 +102,10 @@ impl MovieDataBase {
         return match res {
             Ok(movies) => {
                 let mut movie_list = vec![];
-                movies.for_each(|movie| match movie {
-                    Ok(movie) => movie_list.push(movie),
-                    Err(_) => {}
+                movies.for_each(|movie| {
+                    if let Ok(movie) = movie {
+                        movie_list.push(movie)
+                    }
                 });
                 return Ok(Some(movie_list));
             }
@@ -94,15 +113,128 @@ impl MovieDataBase {
         };
     }
 
-    pub async fn update_movie_with(new_movie: Movie) -> Result<bool, ServiceError> {
+    pub async fn search_movies(
+        query: &str,
+        household_id: &str,
+    ) -> Result<Vec<(Movie, f32)>, ServiceError> {
+        let movies = MovieDataBase::get_all_movies(household_id)
+            .await?
+            .unwrap_or_default();
+        let mut matches: Vec<(Movie, f32)> = movies
+            .into_iter()
+            .map(|movie| {
+                let score = title_similarity(query, &movie.title);
+                (movie, score)
+            })
+            .filter(|(_, score)| *score >= MATCH_THRESHOLD)
+            .collect();
+        matches.sort_by(|left, right| right.1.total_cmp(&left.1));
+        return Ok(matches);
+    }
+
+    pub async fn find_duplicates(
+        new_movie: &Movie,
+        household_id: &str,
+    ) -> Result<Vec<(Movie, f32)>, ServiceError> {
+        // only an exact match on the normalized title blocks an insert,
+        // near misses are left to the lookup and duplicate cluster listings
+        let title = normalize_title(&new_movie.title);
+        let movies = MovieDataBase::get_all_movies(household_id)
+            .await?
+            .unwrap_or_default();
+        return Ok(movies
+            .into_iter()
+            .filter(|movie| {
+                movie.format == new_movie.format && normalize_title(&movie.title) == title
+            })
+            .map(|movie| (movie, 1.0))
+            .collect());
+    }
+
+    pub async fn get_duplicate_clusters(
+        household_id: &str,
+    ) -> Result<Vec<Vec<Movie>>, ServiceError> {
+        let mut remaining = MovieDataBase::get_all_movies(household_id)
+            .await?
+            .unwrap_or_default();
+        let mut clusters = vec![];
+        while let Some(first) = remaining.pop() {
+            let (mut cluster, rest): (Vec<Movie>, Vec<Movie>) =
+                remaining.into_iter().partition(|other| {
+                    other.format == first.format
+                        && title_similarity(&first.title, &other.title) >= LIKELY_SAME_THRESHOLD
+                });
+            remaining = rest;
+            if !cluster.is_empty() {
+                cluster.insert(0, first);
+                clusters.push(cluster);
+            }
+        }
+        return Ok(clusters);
+    }
+
+    pub async fn merge_movies(
+        request: MergeRequest,
+        household_id: &str,
+    ) -> Result<usize, ServiceError> {
+        if !request.is_valid() {
+            return Err(ServiceError::InvalidMergeRequest);
+        }
+        let mut conn = get_connection()?;
+        let tx = conn.transaction().or(Err(ServiceError::FailedToMerge))?;
+        let format_of = |id: &str| {
+            return tx
+                .query_row(
+                    "SELECT format FROM movies WHERE id=?1 AND household_id=?2",
+                    [id, household_id],
+                    |row| row.get::<usize, String>(0),
+                )
+                .optional()
+                .or(Err(ServiceError::FailedToMerge))?
+                .ok_or(ServiceError::MovieNotFound);
+        };
+        let survivor_format = format_of(&request.survivor_id)?;
+        for duplicate_id in &request.duplicate_ids {
+            if format_of(duplicate_id)? != survivor_format {
+                return Err(ServiceError::MismatchedMerge);
+            }
+            reassign_media_references(
+                &tx,
+                MediaKind::Movie,
+                household_id,
+                &request.survivor_id,
+                duplicate_id,
+            )
+            .or(Err(ServiceError::FailedToMerge))?;
+            let removed = tx
+                .execute(
+                    "DELETE FROM movies WHERE id=?1 AND household_id=?2",
+                    [duplicate_id.as_str(), household_id],
+                )
+                .or(Err(ServiceError::FailedToMerge))?;
+            if removed == 0 {
+                return Err(ServiceError::MovieNotFound);
+            }
+        }
+        tx.commit().or(Err(ServiceError::FailedToMerge))?;
+        return Ok(request.duplicate_ids.len());
+    }
+
+    pub async fn update_movie_with(
+        new_movie: Movie,
+        household_id: &str,
+    ) -> Result<bool, ServiceError> {
         let conn = get_connection()?;
         let res = conn.execute(
-            "UPDATE movies SET title=1?, format=2?, rating=3? WHERE id=4?",
-            [
+            "UPDATE movies SET title=?1, format=?2, rating=?3, runtime_minutes=?4
+             WHERE id=?5 AND household_id=?6",
+            params![
                 new_movie.title,
                 new_movie.format.string(),
                 new_movie.rating.string(),
+                new_movie.runtime_minutes,
                 new_movie.id,
+                household_id,
             ],
         );
         return match res {
@@ -117,12 +249,22 @@ impl MovieDataBase {
         };
     }
 
-    pub async fn delete_movie(id: Option<String>) -> Result<bool, ServiceError> {
-        let conn = get_connection()?;
-        let res = match id {
-            Some(id) => conn.execute("DELETE FROM movies WHERE id=1?", [id]),
-            None => conn.execute("DROP TABLE movies", []),
-        };
+    pub async fn delete_movie(
+        id: Option<String>,
+        household_id: &str,
+    ) -> Result<bool, ServiceError> {
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let res = remove_media_references(&tx, MediaKind::Movie, household_id, id.as_deref())
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM movies WHERE household_id=?1 AND (?2 IS NULL OR id=?2)",
+                    params![household_id, id],
+                )
+            })
+            .and_then(|_| tx.commit());
 
         return match res {
             Ok(_) => Ok(true),
@@ -0,0 +1,241 @@
+use crate::data_models::auth_models::User;
+use crate::data_models::game::{ESRBRating, Game};
+use crate::data_models::lookup::MediaKind;
+use crate::data_models::movie::{MPAARating, Movie};
+use crate::data_models::parental_controls::{
+    RatingException, RatingExceptionRequest, RatingLimits,
+};
+use crate::database_services::database_utilities::get_connection;
+use crate::database_services::household_database::HouseholdDataBase;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::params;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub struct ParentalControls {
+    pub limits: RatingLimits,
+    exceptions: HashSet<(String, String)>,
+    one_time_exceptions: HashSet<(String, String)>,
+}
+
+impl ParentalControls {
+    pub async fn for_user(user: &User) -> Result<ParentalControls, ServiceError> {
+        let limits = ParentalControls::get_limits(&user.user_id).await?;
+        let mut exceptions = HashSet::new();
+        let mut one_time_exceptions = HashSet::new();
+        for exception in
+            ParentalControls::get_exceptions(&user.household_id, Some(&user.user_id)).await?
+        {
+            let key = (exception.media_kind.string(), exception.media_id);
+            // one time approvals never count towards listings, only towards opening the item
+            if exception.one_time {
+                one_time_exceptions.insert(key);
+            } else {
+                exceptions.insert(key);
+            }
+        }
+        return Ok(ParentalControls {
+            limits,
+            exceptions,
+            one_time_exceptions,
+        });
+    }
+
+    pub fn has_one_time_exception(&self, kind: MediaKind, id: &str) -> bool {
+        return self
+            .one_time_exceptions
+            .contains(&(kind.string(), id.to_string()));
+    }
+
+    // one time approvals are used up the first time the item is opened,
+    // gives back whether there was one to use
+    pub async fn use_one_time_exception(
+        &self,
+        user: &User,
+        kind: MediaKind,
+        id: &str,
+    ) -> Result<bool, ServiceError> {
+        if !self.has_one_time_exception(kind, id) {
+            return Ok(false);
+        }
+        let conn = get_connection()?;
+        conn.execute(
+            "DELETE FROM rating_exceptions
+             WHERE user_id=?1 AND media_kind=?2 AND media_id=?3 AND one_time=1",
+            [user.user_id.as_str(), &kind.string(), id],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(true);
+    }
+
+    fn has_exception(&self, kind: MediaKind, id: &str) -> bool {
+        return self.exceptions.contains(&(kind.string(), id.to_string()));
+    }
+
+    // games are compared on the age their strictest rating asks for,
+    // an unrated game is kept from anyone with a limit
+    pub fn allows_game(&self, game: &Game) -> bool {
+        return match self.limits.game_age_limit() {
+            Some(max_age) => {
+                game.minimum_age()
+                    .map(|age| age <= max_age)
+                    .unwrap_or(false)
+                    || self.has_exception(MediaKind::Game, &game.id)
+            }
+            None => true,
+        };
+    }
+
+    // movies are compared on ages the same way games are
+    pub fn allows_movie(&self, movie: &Movie) -> bool {
+        return match self.limits.movie_age_limit() {
+            Some(max_age) => {
+                movie.rating.minimum_age() <= max_age
+                    || self.has_exception(MediaKind::Movie, &movie.id)
+            }
+            None => true,
+        };
+    }
+
+    pub async fn get_limits(user_id: &str) -> Result<RatingLimits, ServiceError> {
+        let conn = get_connection()?;
+        let res = conn.query_row(
+            "SELECT max_esrb_rating, max_mpaa_rating, max_game_age FROM users WHERE id=?1",
+            [user_id],
+            |row| {
+                let esrb: Option<String> = row.get(0)?;
+                let mpaa: Option<String> = row.get(1)?;
+                return Ok(RatingLimits {
+                    max_esrb_rating: esrb
+                        .and_then(|rating| ESRBRating::rating_from_string(&rating)),
+                    max_mpaa_rating: mpaa.and_then(|rating| MPAARating::from_string(&rating)),
+                    max_game_age: row.get(2)?,
+                });
+            },
+        );
+        return res.or(Err(ServiceError::MemberNotFound));
+    }
+
+    // The highest movie rating everyone in the group is allowed to watch,
+    // attendees are household members picked by username or id
+    pub async fn strictest_movie_limit(
+        household_id: &str,
+        attendees: &[String],
+    ) -> Result<Option<MPAARating>, ServiceError> {
+        let members = HouseholdDataBase::get_members(household_id).await?;
+        let mut ceiling: Option<MPAARating> = None;
+        for attendee in attendees {
+            let member = members
+                .iter()
+                .find(|member| &member.username == attendee || &member.user_id == attendee)
+                .ok_or(ServiceError::MemberNotFound)?;
+            let limits = ParentalControls::get_limits(&member.user_id).await?;
+            if let Some(max_rating) = limits.max_mpaa_rating {
+                if ceiling.map(|current| max_rating < current).unwrap_or(true) {
+                    ceiling = Some(max_rating);
+                }
+            }
+        }
+        return Ok(ceiling);
+    }
+
+    pub async fn set_limits(
+        household_id: &str,
+        user_id: &str,
+        limits: RatingLimits,
+    ) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let updated = conn
+            .execute(
+                "UPDATE users SET max_esrb_rating=?1, max_mpaa_rating=?2, max_game_age=?3
+                 WHERE id=?4 AND household_id=?5",
+                params![
+                    limits.max_esrb_rating.map(|rating| rating.string()),
+                    limits.max_mpaa_rating.map(|rating| rating.string()),
+                    limits.max_game_age,
+                    user_id,
+                    household_id
+                ],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if updated == 0 {
+            return Err(ServiceError::MemberNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn get_exceptions(
+        household_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<RatingException>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, user_id, media_kind, media_id, one_time FROM rating_exceptions
+                 WHERE household_id=?1 AND (?2 IS NULL OR user_id=?2)",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let exceptions = statement
+            .query_map(params![household_id, user_id], |row| {
+                let kind: String = row.get(2)?;
+                return Ok(RatingException {
+                    id: row.get(0)?,
+                    user_id: row.get(1)?,
+                    media_kind: MediaKind::from_string(&kind).unwrap_or(MediaKind::Game),
+                    media_id: row.get(3)?,
+                    one_time: row.get(4)?,
+                });
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(exceptions.filter_map(|exception| exception.ok()).collect());
+    }
+
+    pub async fn add_exception(
+        household_id: &str,
+        request: RatingExceptionRequest,
+    ) -> Result<RatingException, ServiceError> {
+        let conn = get_connection()?;
+        let is_member = conn
+            .query_row(
+                "SELECT COUNT(*) FROM users WHERE id=?1 AND household_id=?2",
+                [request.user_id.as_str(), household_id],
+                |row| row.get::<usize, usize>(0),
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if is_member == 0 {
+            return Err(ServiceError::MemberNotFound);
+        }
+        let id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO rating_exceptions (id, household_id, user_id, media_kind, media_id, one_time)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                household_id,
+                request.user_id,
+                request.media_kind.string(),
+                request.media_id,
+                request.one_time
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(RatingException {
+            id,
+            user_id: request.user_id,
+            media_kind: request.media_kind,
+            media_id: request.media_id,
+            one_time: request.one_time,
+        });
+    }
+
+    pub async fn remove_exception(household_id: &str, id: &str) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let removed = conn
+            .execute(
+                "DELETE FROM rating_exceptions WHERE id=?1 AND household_id=?2",
+                [id, household_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(removed > 0);
+    }
+}
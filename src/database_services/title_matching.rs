@@ -0,0 +1,129 @@
+pub const MATCH_THRESHOLD: f32 = 0.6;
+pub const LIKELY_SAME_THRESHOLD: f32 = 0.9;
+
+const ARTICLES: [&str; 3] = ["the", "a", "an"];
+
+pub fn normalize_title(title: &str) -> String {
+    let lowered = title.to_lowercase().replace('&', " and ");
+    let cleaned: String = lowered
+        .chars()
+        .filter(|c| *c != '\'' && *c != '’')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let words: Vec<&str> = cleaned.split_whitespace().collect();
+    let mut tokens: Vec<String> = words
+        .iter()
+        .enumerate()
+        .map(|(index, token)| {
+            if is_sequel_position(index, words.len(), token) {
+                return roman_to_arabic(token).unwrap_or(token.to_string());
+            }
+            return token.to_string();
+        })
+        .collect();
+    // "Legend of Zelda, The" and "The Legend of Zelda" should end up the same
+    if tokens.len() > 1 && ARTICLES.contains(&tokens[0].as_str()) {
+        tokens.remove(0);
+    }
+    if tokens.len() > 1 && tokens.last().map(|t| t == "the").unwrap_or(false) {
+        tokens.pop();
+    }
+    return tokens.join(" ");
+}
+
+// Numerals count only after the series name, either at the end or as a longer numeral before
+// a subtitle. A lone "x" or "v" is too often a word of its own, as in "Mega Man X" or "V for Vendetta".
+fn is_sequel_position(index: usize, length: usize, token: &str) -> bool {
+    return index > 0 && token != "x" && token != "v" && (index == length - 1 || token.len() > 1);
+}
+
+// Only i, v and x are handled so words like "mix" or "dc" are left alone
+fn roman_to_arabic(token: &str) -> Option<String> {
+    if token.is_empty() || !token.chars().all(|c| matches!(c, 'i' | 'v' | 'x')) {
+        return None;
+    }
+    let values: Vec<u32> = token
+        .chars()
+        .map(|c| match c {
+            'i' => 1,
+            'v' => 5,
+            _ => 10,
+        })
+        .collect();
+    let mut total = 0;
+    for (index, value) in values.iter().enumerate() {
+        match values.get(index + 1) {
+            Some(next) if next > value => total -= *value as i32,
+            _ => total += *value as i32,
+        }
+    }
+    if total <= 0 || total >= 40 {
+        return None;
+    }
+    return Some(total.to_string());
+}
+
+fn levenshtein(left: &str, right: &str) -> usize {
+    let right_chars: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right_chars.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right_chars.iter().enumerate() {
+            let cost = if left_char == *right_char { 0 } else { 1 };
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    return previous[right_chars.len()];
+}
+
+fn ratio(left: &str, right: &str) -> f32 {
+    let longest = left.chars().count().max(right.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    return 1.0 - levenshtein(left, right) as f32 / longest as f32;
+}
+
+fn numbers(normalized: &str) -> Vec<&str> {
+    return normalized
+        .split(' ')
+        .filter(|token| token.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+}
+
+pub fn title_similarity(query: &str, title: &str) -> f32 {
+    let query = normalize_title(query);
+    let title = normalize_title(title);
+    if query.is_empty() || title.is_empty() {
+        return 0.0;
+    }
+    if query == title {
+        return 1.0;
+    }
+    let whole = ratio(&query, &title);
+    // a partial query like "zelda" should still find "legend of zelda ocarina of time"
+    let title_tokens: Vec<&str> = title.split(' ').collect();
+    let all_tokens_found = query.split(' ').all(|query_token| {
+        title_tokens
+            .iter()
+            .any(|title_token| ratio(query_token, title_token) >= 0.8)
+    });
+    let partial = if all_tokens_found {
+        0.6 + 0.3 * (query.len() as f32 / title.len() as f32).min(1.0)
+    } else {
+        0.0
+    };
+    let mut score = whole.max(partial);
+    // "syphon filter 2" and "syphon filter 3" are different games
+    let query_numbers = numbers(&query);
+    let title_numbers = numbers(&title);
+    if !query_numbers.is_empty() && !title_numbers.is_empty() && query_numbers != title_numbers {
+        score *= 0.7;
+    }
+    return score;
+}
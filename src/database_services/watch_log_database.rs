@@ -0,0 +1,87 @@
+use crate::data_models::auth_models::User;
+use crate::data_models::lookup::MediaKind;
+use crate::data_models::watch_log::{WatchLogEntry, WatchRequest};
+use crate::database_services::database_utilities::{get_connection, today, validate_date};
+use crate::database_services::movie_database::MovieDataBase;
+use crate::errors::servive_errors::ServiceError;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct WatchLogDataBase;
+impl WatchLogDataBase {
+    pub async fn log_watch(
+        user: &User,
+        movie_id: &str,
+        request: WatchRequest,
+    ) -> Result<WatchLogEntry, ServiceError> {
+        MovieDataBase::get_movie_with_id(movie_id.to_string(), &user.household_id)
+            .await?
+            .ok_or(ServiceError::MovieNotFound)?;
+        let entry = WatchLogEntry {
+            id: Uuid::new_v4().to_string(),
+            media_kind: MediaKind::Movie,
+            media_id: movie_id.to_string(),
+            user_id: user.user_id.clone(),
+            watched_on: validate_date(request.watched_on)?.unwrap_or_else(today),
+        };
+        let conn = get_connection()?;
+        conn.execute(
+            "INSERT INTO watch_log (id, household_id, media_kind, media_id, user_id, watched_on)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            [
+                entry.id.as_str(),
+                &user.household_id,
+                &entry.media_kind.string(),
+                &entry.media_id,
+                &entry.user_id,
+                &entry.watched_on,
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(entry);
+    }
+
+    pub async fn get_history(
+        household_id: &str,
+        movie_id: &str,
+    ) -> Result<Vec<WatchLogEntry>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, media_kind, media_id, user_id, watched_on FROM watch_log
+                 WHERE household_id=?1 AND media_kind='Movie' AND media_id=?2
+                 ORDER BY watched_on DESC",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let entries = statement
+            .query_map([household_id, movie_id], |row| {
+                return Ok(WatchLogEntry {
+                    id: row.get(0)?,
+                    media_kind: MediaKind::from_string(&row.get::<usize, String>(1)?)
+                        .unwrap_or(MediaKind::Movie),
+                    media_id: row.get(2)?,
+                    user_id: row.get(3)?,
+                    watched_on: row.get(4)?,
+                });
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(entries.filter_map(|entry| entry.ok()).collect());
+    }
+
+    // The most recent watch date for every movie that has been watched at all
+    pub async fn last_watched(household_id: &str) -> Result<HashMap<String, String>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT media_id, MAX(watched_on) FROM watch_log
+                 WHERE household_id=?1 AND media_kind='Movie' GROUP BY media_id",
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let dates = statement
+            .query_map([household_id], |row| {
+                return Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?));
+            })
+            .or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(dates.filter_map(|date| date.ok()).collect());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::game::PlatformType;
+use crate::data_models::lookup::MediaKind;
+use crate::data_models::movie::MotionPictureFormat;
+use crate::data_models::wishlist::{NewWishlistItem, WishlistItem};
+use crate::database_services::database_utilities::{get_connection, validate_date};
+use crate::database_services::title_matching::normalize_title;
+use crate::errors::servive_errors::ServiceError;
+use rusqlite::{params, OptionalExtension, Row};
+use uuid::Uuid;
+
+const WISHLIST_QUERY: &str = "SELECT w.id, w.user_id, u.username, w.media_kind, w.title,
+    w.platform, w.format, w.release_date, w.notes, w.media_id, claimer.username
+    FROM wishlist_items AS w
+    INNER JOIN users AS u ON w.user_id = u.id
+    LEFT JOIN users AS claimer ON w.claimed_by = claimer.id";
+
+fn wishlist_item_from_row(row: &Row) -> Result<WishlistItem, rusqlite::Error> {
+    let claimed_by: Option<String> = row.get(10)?;
+    return Ok(WishlistItem {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        username: row.get(2)?,
+        media_kind: MediaKind::from_string(&row.get::<usize, String>(3)?)
+            .unwrap_or(MediaKind::Game),
+        title: row.get(4)?,
+        platform: row
+            .get::<usize, Option<String>>(5)?
+            .and_then(|platform| PlatformType::platform_from_string(&platform)),
+        format: row
+            .get::<usize, Option<String>>(6)?
+            .and_then(|format| MotionPictureFormat::from_string(&format)),
+        release_date: row.get(7)?,
+        notes: row.get(8)?,
+        media_id: row.get(9)?,
+        claimed: Some(claimed_by.is_some()),
+        claimed_by,
+    });
+}
+
+pub struct WishlistDataBase;
+impl WishlistDataBase {
+    pub async fn get_items(
+        household_id: &str,
+        user_id: Option<&str>,
+        include_resolved: bool,
+    ) -> Result<Vec<WishlistItem>, ServiceError> {
+        let conn = get_connection()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "{WISHLIST_QUERY}
+                 WHERE w.household_id=?1 AND (?2 IS NULL OR w.user_id=?2)
+                 AND (?3 OR w.media_id IS NULL)
+                 ORDER BY u.username, w.title COLLATE NOCASE"
+            ))
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let items = statement
+            .query_map(
+                params![household_id, user_id, include_resolved],
+                wishlist_item_from_row,
+            )
+            .or(Err(ServiceError::WishlistItemNotFound))?;
+        return Ok(items.filter_map(|item| item.ok()).collect());
+    }
+
+    pub async fn get_item(household_id: &str, item_id: &str) -> Result<WishlistItem, ServiceError> {
+        let conn = get_connection()?;
+        return conn
+            .query_row(
+                &format!("{WISHLIST_QUERY} WHERE w.household_id=?1 AND w.id=?2"),
+                [household_id, item_id],
+                wishlist_item_from_row,
+            )
+            .optional()
+            .or(Err(ServiceError::ConnectionFailure))?
+            .ok_or(ServiceError::WishlistItemNotFound);
+    }
+
+    pub async fn add_item(
+        user: &User,
+        request: NewWishlistItem,
+    ) -> Result<WishlistItem, ServiceError> {
+        if !request.is_valid() {
+            return Err(ServiceError::InvalidWishlistItem);
+        }
+        let release_date = validate_date(request.release_date)?;
+        let id = Uuid::new_v4().to_string();
+        let conn = get_connection()?;
+        conn.execute(
+            "INSERT INTO wishlist_items
+             (id, household_id, user_id, media_kind, title, platform, format, release_date, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                id,
+                user.household_id,
+                user.user_id,
+                request.media_kind.string(),
+                request.title.trim(),
+                request.platform.map(|platform| platform.string()),
+                request.format.map(|format| format.string()),
+                release_date,
+                request.notes
+            ],
+        )
+        .or(Err(ServiceError::ConnectionFailure))?;
+        return WishlistDataBase::get_item(&user.household_id, &id).await;
+    }
+
+    pub async fn delete_item(user: &User, item_id: &str) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let any_member = user.role.allows(Permission::ManageMembers);
+        let removed = conn
+            .execute(
+                "DELETE FROM wishlist_items WHERE id=?1 AND household_id=?2 AND (?3 OR user_id=?4)",
+                params![item_id, user.household_id, any_member, user.user_id],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if removed == 0 {
+            return Err(ServiceError::WishlistItemNotFound);
+        }
+        return Ok(true);
+    }
+
+    pub async fn claim_item(user: &User, item_id: &str) -> Result<bool, ServiceError> {
+        let item = WishlistDataBase::get_item(&user.household_id, item_id).await?;
+        if item.user_id == user.user_id {
+            return Err(ServiceError::CannotClaimOwnWish);
+        }
+        let conn = get_connection()?;
+        let claimed = conn
+            .execute(
+                "UPDATE wishlist_items SET claimed_by=?1
+                 WHERE id=?2 AND household_id=?3 AND claimed_by IS NULL AND media_id IS NULL",
+                [user.user_id.as_str(), item_id, user.household_id.as_str()],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if claimed == 0 {
+            return Err(ServiceError::AlreadyClaimed);
+        }
+        return Ok(true);
+    }
+
+    pub async fn unclaim_item(user: &User, item_id: &str) -> Result<bool, ServiceError> {
+        let conn = get_connection()?;
+        let released = conn
+            .execute(
+                "UPDATE wishlist_items SET claimed_by=NULL
+                 WHERE id=?1 AND household_id=?2 AND claimed_by=?3",
+                [item_id, user.household_id.as_str(), user.user_id.as_str()],
+            )
+            .or(Err(ServiceError::ConnectionFailure))?;
+        if released == 0 {
+            return Err(ServiceError::WishlistItemNotFound);
+        }
+        return Ok(true);
+    }
+
+    // Called after a game or movie is added so that matching wishes are marked as
+    // fulfilled, a wish without a platform or format matches any of them
+    pub async fn resolve_matching(
+        household_id: &str,
+        kind: MediaKind,
+        title: &str,
+        variant: &str,
+        media_id: &str,
+    ) -> Result<usize, ServiceError> {
+        let variant_column = match kind {
+            MediaKind::Game => "platform",
+            MediaKind::Movie => "format",
+        };
+        let mut conn = get_connection()?;
+        let tx = conn
+            .transaction()
+            .or(Err(ServiceError::ConnectionFailure))?;
+        let candidates: Vec<(String, String)> = {
+            let mut statement = tx
+                .prepare(&format!(
+                    "SELECT id, title FROM wishlist_items
+                     WHERE household_id=?1 AND media_kind=?2 AND media_id IS NULL
+                     AND ({variant_column} IS NULL OR {variant_column}=?3)"
+                ))
+                .or(Err(ServiceError::ConnectionFailure))?;
+            let rows = statement
+                .query_map([household_id, &kind.string(), variant], |row| {
+                    return Ok((row.get(0)?, row.get(1)?));
+                })
+                .or(Err(ServiceError::ConnectionFailure))?;
+            rows.filter_map(|row| row.ok()).collect()
+        };
+        let normalized = normalize_title(title);
+        let mut resolved = 0;
+        for (item_id, wished_title) in candidates {
+            if normalize_title(&wished_title) == normalized {
+                resolved += tx
+                    .execute(
+                        "UPDATE wishlist_items SET media_id=?1 WHERE id=?2",
+                        [media_id, item_id.as_str()],
+                    )
+                    .or(Err(ServiceError::ConnectionFailure))?;
+            }
+        }
+        tx.commit().or(Err(ServiceError::ConnectionFailure))?;
+        return Ok(resolved);
+    }
+}
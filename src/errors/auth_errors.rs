@@ -17,6 +17,10 @@ pub enum AuthServiceError {
     GenerallyForbiden,
     #[display("There might be an attacker in the middle")]
     SuspiciousRequest,
+    #[display("No session found for specified id")]
+    SessionNotFound,
+    #[display("Invalid calendar token")]
+    InvalidCalendarToken,
 }
 
 impl error::ResponseError for AuthServiceError {
@@ -33,6 +37,8 @@ impl error::ResponseError for AuthServiceError {
             Self::FailedToAuthenticate => StatusCode::UNAUTHORIZED,
             Self::SuspiciousRequest => StatusCode::UNAUTHORIZED,
             Self::InvalidSessionToken => StatusCode::UNAUTHORIZED,
+            Self::SessionNotFound => StatusCode::NOT_FOUND,
+            Self::InvalidCalendarToken => StatusCode::NOT_FOUND,
         }
     }
 }
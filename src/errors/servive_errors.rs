@@ -1,9 +1,12 @@
+use crate::data_models::lookup::LookupCandidate;
+use crate::errors::auth_errors::AuthServiceError;
 use actix_web::{
     error,
     http::{header::ContentType, StatusCode},
     HttpResponse,
 };
 use derive_more::derive::{Display, Error};
+use serde_json::json;
 
 #[derive(Debug, Display, Error)]
 pub enum ServiceError {
@@ -21,10 +24,98 @@ pub enum ServiceError {
     FailedToUpdateGame,
     #[display("Failed to update movie")]
     FailedToUpdateMovie,
+    #[display("A title to look up is required")]
+    MissingSearchTerm,
+    #[display("This looks like something already in the library, add a copy to it instead")]
+    DuplicateFound(#[error(not(source))] Vec<LookupCandidate>),
+    #[display("A merge needs a surviving record and at least one other duplicate")]
+    InvalidMergeRequest,
+    #[display("Only records on the same platform or format can be merged")]
+    MismatchedMerge,
+    #[display("Failed to merge duplicates")]
+    FailedToMerge,
+    #[display("{_0}")]
+    NotPermitted(AuthServiceError),
+    #[display("Failed to find a household member for specified id")]
+    MemberNotFound,
+    #[display("A household needs at least one owner")]
+    LastOwner,
+    #[display("The last member can't leave a household that still has a collection")]
+    HouseholdNotEmpty,
+    #[display("Invite code is invalid or has expired")]
+    InvalidInvite,
+    #[display("Failed to find a pending request for specified id")]
+    RequestNotFound,
+    #[display("This is already allowed, no need to ask")]
+    RequestNotNeeded,
+    #[display("There's already a pending request for this")]
+    DuplicateRequest,
+    #[display("Dates need to look like 2025-01-31")]
+    InvalidDate,
+    #[display("A loan can't be due before it was lent out")]
+    DueBeforeLent,
+    #[display("A loan can't come back before it was lent out")]
+    ReturnedBeforeLent,
+    #[display("That is already lent out")]
+    AlreadyOnLoan,
+    #[display("Failed to find an open loan for specified id")]
+    LoanNotFound,
+    #[display("Failed to find contact for specified id")]
+    ContactNotFound,
+    #[display("Contacts need a name")]
+    InvalidContact,
+    #[display("A loan needs a borrower name or a contact")]
+    MissingBorrower,
+    #[display("Failed to find wishlist item for specified id")]
+    WishlistItemNotFound,
+    #[display("Wishlist items need a title and a platform or format that fits the kind")]
+    InvalidWishlistItem,
+    #[display("You can't claim something off your own wishlist")]
+    CannotClaimOwnWish,
+    #[display("Someone has already claimed that")]
+    AlreadyClaimed,
+    #[display("Failed to find location for specified id")]
+    LocationNotFound,
+    #[display("Locations need a name and can't be placed inside themselves")]
+    InvalidLocation,
+    #[display("Move everything out of this location before removing it")]
+    LocationNotEmpty,
+    #[display("Failed to find copy for specified id")]
+    CopyNotFound,
+    #[display("Failed to save copy")]
+    FailedToSaveCopy,
+    #[display("A title needs at least one copy, delete the title instead")]
+    LastCopy,
+    #[display("Region codes are digits for DVDs and letters A to C for Blu-rays")]
+    InvalidRegionCode,
+    #[display("Failed to find hardware for specified id")]
+    HardwareNotFound,
+    #[display("Consoles and controllers need a platform, players need a format")]
+    InvalidHardware,
+    #[display("Pick a platform or one of your consoles")]
+    InvalidPlayableQuery,
+    #[display("A plan needs at least one player")]
+    InvalidPlanQuery,
+    #[display("Player counts need at least one player and a maximum no lower than the minimum")]
+    InvalidPlayerCounts,
+    #[display("Games need a rating from at least one board, using that board's own labels")]
+    InvalidRating,
+}
+
+impl From<AuthServiceError> for ServiceError {
+    fn from(error: AuthServiceError) -> Self {
+        return ServiceError::NotPermitted(error);
+    }
 }
 
 impl error::ResponseError for ServiceError {
     fn error_response(&self) -> HttpResponse<actix_web::body::BoxBody> {
+        if let Self::DuplicateFound(matches) = self {
+            return HttpResponse::build(self.status_code()).json(json!({
+                "message": self.to_string(),
+                "matches": matches,
+            }));
+        }
         return HttpResponse::build(self.status_code())
             .insert_header(ContentType::plaintext())
             .body(self.to_string());
@@ -39,6 +130,44 @@ impl error::ResponseError for ServiceError {
             Self::FailedToMakeMovie => StatusCode::IM_A_TEAPOT,
             Self::FailedToUpdateGame => StatusCode::INTERNAL_SERVER_ERROR,
             Self::FailedToUpdateMovie => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::MissingSearchTerm => StatusCode::BAD_REQUEST,
+            Self::DuplicateFound(_) => StatusCode::CONFLICT,
+            Self::InvalidMergeRequest => StatusCode::BAD_REQUEST,
+            Self::MismatchedMerge => StatusCode::BAD_REQUEST,
+            Self::FailedToMerge => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NotPermitted(error) => error.status_code(),
+            Self::MemberNotFound => StatusCode::NOT_FOUND,
+            Self::LastOwner => StatusCode::CONFLICT,
+            Self::HouseholdNotEmpty => StatusCode::CONFLICT,
+            Self::InvalidInvite => StatusCode::BAD_REQUEST,
+            Self::RequestNotFound => StatusCode::NOT_FOUND,
+            Self::RequestNotNeeded => StatusCode::BAD_REQUEST,
+            Self::DuplicateRequest => StatusCode::CONFLICT,
+            Self::InvalidDate => StatusCode::BAD_REQUEST,
+            Self::DueBeforeLent => StatusCode::BAD_REQUEST,
+            Self::ReturnedBeforeLent => StatusCode::BAD_REQUEST,
+            Self::AlreadyOnLoan => StatusCode::CONFLICT,
+            Self::LoanNotFound => StatusCode::NOT_FOUND,
+            Self::ContactNotFound => StatusCode::NOT_FOUND,
+            Self::InvalidContact => StatusCode::BAD_REQUEST,
+            Self::MissingBorrower => StatusCode::BAD_REQUEST,
+            Self::WishlistItemNotFound => StatusCode::NOT_FOUND,
+            Self::InvalidWishlistItem => StatusCode::BAD_REQUEST,
+            Self::CannotClaimOwnWish => StatusCode::BAD_REQUEST,
+            Self::AlreadyClaimed => StatusCode::CONFLICT,
+            Self::LocationNotFound => StatusCode::NOT_FOUND,
+            Self::InvalidLocation => StatusCode::BAD_REQUEST,
+            Self::LocationNotEmpty => StatusCode::CONFLICT,
+            Self::CopyNotFound => StatusCode::NOT_FOUND,
+            Self::FailedToSaveCopy => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::LastCopy => StatusCode::CONFLICT,
+            Self::InvalidRegionCode => StatusCode::BAD_REQUEST,
+            Self::HardwareNotFound => StatusCode::NOT_FOUND,
+            Self::InvalidHardware => StatusCode::BAD_REQUEST,
+            Self::InvalidPlayableQuery => StatusCode::BAD_REQUEST,
+            Self::InvalidPlanQuery => StatusCode::BAD_REQUEST,
+            Self::InvalidPlayerCounts => StatusCode::BAD_REQUEST,
+            Self::InvalidRating => StatusCode::BAD_REQUEST,
         };
     }
 }
@@ -1,37 +1,41 @@
+#![allow(clippy::needless_return)]
+
+use crate::config::SessionConfig;
+use crate::middleware::{my_middleware, require_session};
 use crate::service_endpoints::{
-    auth_servce::auth_scope, game_service::game_scope, movie_service::movie_scope,
-};
-use actix_web::{
-    body::MessageBody,
-    dev::{ServiceRequest, ServiceResponse},
-    middleware::{from_fn, Next},
-    App, Error, HttpMessage, HttpServer,
+    auth_servce::auth_scope, calendar_service::calendar_scope, contact_service::contact_scope,
+    copy_service::copy_scope, game_service::game_scope, hardware_service::hardware_scope,
+    household_service::household_scope, loan_service::loan_scope, location_service::location_scope,
+    lookup_service::lookup_scope, movie_service::movie_scope, request_service::request_scope,
+    wishlist_service::wishlist_scope,
 };
+use actix_web::{middleware::from_fn, App, HttpServer};
 
+pub mod config;
 pub mod data_models;
 pub mod database_services;
 pub mod errors;
+pub mod middleware;
 pub mod service_endpoints;
 
-//TODO: Move to own module
-async fn my_middleware(
-    req: ServiceRequest,
-    next: Next<impl MessageBody>,
-) -> Result<ServiceResponse<impl MessageBody>, Error> {
-    println!("{}", req.path());
-    println!("{}", req.content_type());
-    println!("{}", req.method());
-    let res = next.call(req).await;
-    return res;
-}
-
 #[actix_web::main]
 async fn main() -> Result<(), std::io::Error> {
+    SessionConfig::init()?;
     HttpServer::new(|| {
         App::new()
-            .service(game_scope())
-            .service(movie_scope())
+            .service(game_scope().wrap(from_fn(require_session)))
+            .service(movie_scope().wrap(from_fn(require_session)))
+            .service(lookup_scope().wrap(from_fn(require_session)))
+            .service(household_scope().wrap(from_fn(require_session)))
+            .service(request_scope().wrap(from_fn(require_session)))
+            .service(loan_scope().wrap(from_fn(require_session)))
+            .service(contact_scope().wrap(from_fn(require_session)))
+            .service(wishlist_scope().wrap(from_fn(require_session)))
+            .service(location_scope().wrap(from_fn(require_session)))
+            .service(copy_scope().wrap(from_fn(require_session)))
+            .service(hardware_scope().wrap(from_fn(require_session)))
             .service(auth_scope())
+            .service(calendar_scope())
             .wrap(from_fn(my_middleware))
     })
     .bind(("127.0.0.1", 8080))?
@@ -42,8 +46,10 @@ async fn main() -> Result<(), std::io::Error> {
 // TODO: Move to test directory
 #[cfg(test)]
 mod tests {
-    use crate::data_models::{game::*, movie::*};
-    use serde_json;
+    use crate::data_models::{
+        calendar::*, copies::*, game::*, hardware::*, movie::*, parental_controls::*, plans::*,
+    };
+    use crate::database_services::title_matching::*;
 
     #[test]
     fn test_movie_encoding() {
@@ -58,12 +64,14 @@ mod tests {
             title: "Troy".to_string(),
             format: MotionPictureFormat::DVD,
             rating: MPAARating::Restricted,
+            runtime_minutes: None,
         };
         let movie: Movie = serde_json::from_str(str_data).unwrap();
         assert_eq!(expected_movie.id, movie.id);
         assert_eq!(expected_movie.title, movie.title);
         assert_eq!(expected_movie.format, movie.format);
         assert_eq!(expected_movie.rating, movie.rating);
+        assert_eq!(expected_movie.runtime_minutes, movie.runtime_minutes);
     }
 
     #[test]
@@ -72,16 +80,25 @@ mod tests {
         \"id\":\"\",
         \"title\":\"syphon filter 2\",
         \"platform\":\"Playstation1\",
-        \"rating\":\"Mature\"
-            ,\"number_of_players\":1
+        \"rating\":\"Mature\",
+        \"min_players\":1,
+        \"max_local_players\":2,
+        \"online\":true
         }";
 
         let expected_game = Game {
             id: "".to_string(),
             title: "syphon filter 2".to_string(),
-            rating: ESRBRating::Mature,
+            rating: Some(ESRBRating::Mature),
+            regional_ratings: vec![],
             platform: PlatformType::Playstation1,
-            number_of_players: 1,
+            players: PlayerCounts {
+                min_players: 1,
+                max_local_players: 2,
+                couch_coop: false,
+                online: true,
+                max_online_players: None,
+            },
         };
 
         let game: Game = serde_json::from_str(str_data).unwrap();
@@ -90,6 +107,277 @@ mod tests {
         assert_eq!(expected_game.title, game.title);
         assert_eq!(expected_game.platform, game.platform);
         assert_eq!(expected_game.rating, game.rating);
-        assert_eq!(expected_game.number_of_players, game.number_of_players);
+        assert_eq!(expected_game.players, game.players);
+        assert!(game.players.supports_online(8));
+        assert!(!game.players.supports_local(3));
+    }
+
+    #[test]
+    fn test_title_normalization() {
+        assert_eq!(normalize_title("The Legend of Zelda"), "legend of zelda");
+        assert_eq!(normalize_title("Legend of Zelda, The"), "legend of zelda");
+        assert_eq!(normalize_title("Final Fantasy VII"), "final fantasy 7");
+        assert_eq!(normalize_title("Assassin's Creed II"), "assassins creed 2");
+        assert_eq!(
+            normalize_title("Street Fighter II Turbo"),
+            "street fighter 2 turbo"
+        );
+        assert_eq!(normalize_title("Mega Man X"), "mega man x");
+        assert_eq!(normalize_title("X-Men"), "x men");
+        assert_eq!(normalize_title("V for Vendetta"), "v for vendetta");
+    }
+
+    #[test]
+    fn test_title_similarity() {
+        assert_eq!(title_similarity("syphon filter II", "Syphon Filter 2"), 1.0);
+        assert!(title_similarity("legnd of zelda", "The Legend of Zelda") >= LIKELY_SAME_THRESHOLD);
+        assert!(title_similarity("zelda", "The Legend of Zelda") >= MATCH_THRESHOLD);
+        assert!(title_similarity("syphon filter 3", "Syphon Filter 2") < LIKELY_SAME_THRESHOLD);
+        assert!(title_similarity("troy", "Mario Kart 64") < MATCH_THRESHOLD);
+        assert!(title_similarity("Mega Man X", "Mega Man 10") < LIKELY_SAME_THRESHOLD);
+    }
+
+    #[test]
+    fn test_copy_completeness() {
+        let mut parts = Parts {
+            has_media: true,
+            ..Default::default()
+        };
+        assert_eq!(parts.completeness(), Completeness::Loose);
+        assert_eq!(parts.missing(), vec![Part::Box, Part::Manual]);
+        parts.has_box = true;
+        assert_eq!(parts.completeness(), Completeness::Partial);
+        parts.has_manual = true;
+        assert_eq!(parts.completeness(), Completeness::CIB);
+        assert!(parts.missing().is_empty());
+        assert!(PlatformType::SNES.is_retro());
+        assert!(!PlatformType::Switch.is_retro());
+    }
+
+    #[test]
+    fn test_hardware_gaps() {
+        let item = |kind: HardwareKind, count: u32, broken: bool| Hardware {
+            id: "".to_string(),
+            name: "PS2".to_string(),
+            kind,
+            platform: Some(PlatformType::Playstation2),
+            format: None,
+            video_standard: None,
+            region_code: None,
+            region_free: false,
+            count,
+            condition: None,
+            broken,
+            revision: None,
+        };
+        let players = PlayerCounts {
+            min_players: 1,
+            max_local_players: 4,
+            couch_coop: false,
+            online: false,
+            max_online_players: None,
+        };
+        let game = Game::new(
+            "TimeSplitters 2".to_string(),
+            "Playstation2",
+            Some("Teen"),
+            vec![],
+            players,
+        )
+        .unwrap();
+        let inventory = HardwareInventory::new(vec![
+            item(HardwareKind::Console, 1, true),
+            item(HardwareKind::Controller, 2, false),
+            item(HardwareKind::Controller, 1, true),
+        ]);
+        assert_eq!(
+            inventory.gaps_for(&game),
+            vec![
+                HardwareGap::NoWorkingConsole,
+                HardwareGap::NotEnoughControllers {
+                    needed: 4,
+                    owned: 2
+                }
+            ]
+        );
+        let inventory = HardwareInventory::new(vec![
+            item(HardwareKind::Console, 1, false),
+            item(HardwareKind::Controller, 4, false),
+        ]);
+        assert!(inventory.gaps_for(&game).is_empty());
+    }
+
+    #[test]
+    fn test_plans_on_newer_hosts() {
+        let item = |kind: HardwareKind, platform: PlatformType, count: u32| Hardware {
+            id: "".to_string(),
+            name: platform.string(),
+            kind,
+            platform: Some(platform),
+            format: None,
+            video_standard: None,
+            region_code: None,
+            region_free: false,
+            count,
+            condition: None,
+            broken: false,
+            revision: None,
+        };
+        let players = PlayerCounts {
+            min_players: 1,
+            max_local_players: 4,
+            couch_coop: true,
+            online: false,
+            max_online_players: None,
+        };
+        let kart_ds = Game::new(
+            "Mario Kart DS".to_string(),
+            "NintendoDS",
+            Some("Everyone"),
+            vec![],
+            players,
+        )
+        .unwrap();
+        let kart_switch = Game::new(
+            "Mario Kart 8 Deluxe".to_string(),
+            "Switch",
+            Some("Everyone"),
+            vec![],
+            players,
+        )
+        .unwrap();
+        let query = GamePlanQuery {
+            players: 2,
+            max_rating: None,
+            max_age: None,
+            mode: Some(PlayMode::Local),
+            pick: false,
+        };
+        let inventory = HardwareInventory::new(vec![
+            item(HardwareKind::Console, PlatformType::Nintendo3DS, 2),
+            item(HardwareKind::Console, PlatformType::Switch2, 1),
+            item(HardwareKind::Controller, PlatformType::Switch2, 2),
+        ]);
+        assert_eq!(inventory.controllers_for(PlatformType::NintendoDS), Some(2));
+        assert_eq!(inventory.controllers_for(PlatformType::Switch), Some(2));
+        let plan = GamePlan::build(
+            vec![kart_ds.clone(), kart_switch.clone()],
+            &inventory,
+            &query,
+        );
+        assert_eq!(plan.local.len(), 2);
+        // a single 3DS can't seat two players, and older hosts never cover newer games
+        let inventory = HardwareInventory::new(vec![
+            item(HardwareKind::Console, PlatformType::Nintendo3DS, 1),
+            item(HardwareKind::Console, PlatformType::Switch, 1),
+            item(HardwareKind::Controller, PlatformType::Switch, 2),
+        ]);
+        assert_eq!(inventory.controllers_for(PlatformType::Switch2), Some(0));
+        let plan = GamePlan::build(vec![kart_ds, kart_switch], &inventory, &query);
+        assert_eq!(
+            plan.local
+                .iter()
+                .map(|game| game.title.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Mario Kart 8 Deluxe"]
+        );
+    }
+
+    #[test]
+    fn test_online_plans() {
+        let players = PlayerCounts {
+            min_players: 1,
+            max_local_players: 1,
+            couch_coop: false,
+            online: true,
+            max_online_players: Some(8),
+        };
+        let socom = Game::new(
+            "SOCOM II".to_string(),
+            "Playstation2",
+            Some("Mature"),
+            vec![],
+            players,
+        )
+        .unwrap();
+        let query = GamePlanQuery {
+            players: 4,
+            max_rating: None,
+            max_age: None,
+            mode: Some(PlayMode::Online),
+            pick: false,
+        };
+        let plan = GamePlan::build(vec![socom], &HardwareInventory::new(vec![]), &query);
+        assert_eq!(plan.online.len(), 1);
+    }
+
+    #[test]
+    fn test_backward_compatibility() {
+        assert!(PlatformType::Playstation3.runs(PlatformType::Playstation1, None));
+        assert!(PlatformType::Playstation3.runs(PlatformType::Playstation2, Some("cechA01")));
+        assert!(!PlatformType::Playstation3.runs(PlatformType::Playstation2, Some("CECH-2501A")));
+        assert!(!PlatformType::Playstation3.runs(PlatformType::Playstation2, None));
+        assert!(PlatformType::Playstation3.can_ever_run(PlatformType::Playstation2));
+        assert!(!PlatformType::Wii.runs(PlatformType::GameCube, Some("RVL-101")));
+        assert!(!PlatformType::Playstation4.can_ever_run(PlatformType::Playstation3));
+        assert!(PlatformType::Switch.runs(PlatformType::Switch, None));
+    }
+
+    #[test]
+    fn test_rating_ages() {
+        let players = PlayerCounts {
+            min_players: 1,
+            max_local_players: 1,
+            couch_coop: false,
+            online: false,
+            max_online_players: None,
+        };
+        let pegi = |rating: &str| RegionalRating {
+            system: RatingSystem::PEGI,
+            rating: rating.to_string(),
+        };
+        let mut game = Game::new(
+            "The Witcher".to_string(),
+            "Computer",
+            None,
+            vec![pegi("16")],
+            players,
+        )
+        .unwrap();
+        assert_eq!(game.minimum_age(), Some(16));
+        assert!(game.has_valid_ratings());
+        game.rating = Some(ESRBRating::Mature);
+        assert_eq!(game.minimum_age(), Some(17));
+        game.regional_ratings.push(pegi("18"));
+        assert!(!game.has_valid_ratings());
+        game.regional_ratings = vec![pegi("15")];
+        assert!(!game.has_valid_ratings());
+        assert_eq!(RatingSystem::CERO.minimum_age("Z"), Some(18));
+        assert_eq!(RatingSystem::ACB.minimum_age("MA15+"), Some(15));
+        let limits = RatingLimits {
+            max_esrb_rating: Some(ESRBRating::Everyone),
+            max_mpaa_rating: Some(MPAARating::ParentalGuidance),
+            max_game_age: None,
+        };
+        game.rating = Some(ESRBRating::Everyone);
+        game.regional_ratings = vec![pegi("3")];
+        assert!(game.minimum_age() <= limits.game_age_limit());
+        assert_eq!(limits.movie_age_limit(), Some(8));
+    }
+
+    #[test]
+    fn test_calendar_rendering() {
+        let event = CalendarEvent {
+            uid: "loan-1".to_string(),
+            date: chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            summary: format!("Troy due back from Ann, Bob; {}", "x".repeat(80)),
+            description: None,
+        };
+        let calendar = render_calendar(&[event]);
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20250131\r\n"));
+        assert!(calendar.contains("DTEND;VALUE=DATE:20250201\r\n"));
+        assert!(calendar.contains("Ann\\, Bob\\;"));
+        assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
     }
 }
@@ -0,0 +1,27 @@
+use crate::database_services::authentication_services::validate_request;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    Error, HttpMessage,
+};
+
+pub async fn my_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    println!("{}", req.path());
+    println!("{}", req.content_type());
+    println!("{}", req.method());
+    let res = next.call(req).await;
+    return res;
+}
+
+pub async fn require_session(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let user = validate_request(&req).await?;
+    req.extensions_mut().insert(user);
+    return next.call(req).await;
+}
@@ -1,3 +1,13 @@
 pub mod auth_servce;
+pub mod calendar_service;
+pub mod contact_service;
+pub mod copy_service;
 pub mod game_service;
+pub mod hardware_service;
+pub mod household_service;
+pub mod loan_service;
+pub mod location_service;
+pub mod lookup_service;
 pub mod movie_service;
+pub mod request_service;
+pub mod wishlist_service;
@@ -1,29 +1,100 @@
+use crate::config::SessionConfig;
 use crate::data_models::auth_models::LoginRequest;
+use crate::data_models::auth_models::User;
+use crate::data_models::calendar::CalendarToken;
 use crate::database_services::authentication_services::{
-    create_session_id, create_user, get_user_id,
+    create_calendar_token, create_session_id, create_user, get_active_sessions, get_user_id,
+    revoke_calendar_token, revoke_session, SESSION_KEY,
 };
 use crate::errors::auth_errors::AuthServiceError;
+use crate::middleware::require_session;
 use actix_web::{
+    cookie::{time::Duration, Cookie, SameSite},
+    delete, get,
+    middleware::from_fn,
     post,
-    web::{scope, Json},
+    web::{scope, Json, Path, ReqData},
     HttpResponse, Responder, Scope,
 };
 
+fn session_cookie(session_id: String) -> Cookie<'static> {
+    return Cookie::build(SESSION_KEY, session_id)
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        // strict keeps other sites from riding the session on destructive requests
+        .same_site(SameSite::Strict)
+        .finish();
+}
+
+fn session_response(user: User, remember_me: bool) -> HttpResponse {
+    let mut cookie = session_cookie(user.user_session.clone());
+    let lifetime = SessionConfig::get().absolute_lifetime_for(remember_me);
+    cookie.set_max_age(Duration::seconds(lifetime.num_seconds()));
+    return HttpResponse::Ok().cookie(cookie).json(user);
+}
+
 #[post("/login")]
 async fn login_user(body: Json<LoginRequest>) -> Result<impl Responder, AuthServiceError> {
     let login_req = body.into_inner();
-    let id = get_user_id(login_req.username.clone(), login_req.pass_hash)?;
-    let user = create_session_id(id, login_req.username)?;
-    Ok(HttpResponse::Ok().json(user))
+    let id = get_user_id(login_req.username.clone(), login_req.password.clone())?;
+    let user = create_session_id(id, &login_req)?;
+    Ok(session_response(user, login_req.remember_me))
 }
 
 #[post("/register")]
 async fn register_user(body: Json<LoginRequest>) -> Result<impl Responder, AuthServiceError> {
     let request = body.into_inner();
+    let remember_me = request.remember_me;
     let user = create_user(request)?;
-    Ok(HttpResponse::Ok().json(user))
+    Ok(session_response(user, remember_me))
+}
+
+#[post("/logout", wrap = "from_fn(require_session)")]
+async fn logout_user(user: ReqData<User>) -> Result<impl Responder, AuthServiceError> {
+    revoke_session(&user, &user.user_session)?;
+    let mut cookie = session_cookie(String::new());
+    cookie.make_removal();
+    Ok(HttpResponse::Ok().cookie(cookie).json(true))
+}
+
+#[get("/sessions", wrap = "from_fn(require_session)")]
+async fn list_sessions(user: ReqData<User>) -> Result<impl Responder, AuthServiceError> {
+    let sessions = get_active_sessions(&user)?;
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+#[delete("/sessions/{id}", wrap = "from_fn(require_session)")]
+async fn delete_session(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, AuthServiceError> {
+    let session_id = path.into_inner().0;
+    revoke_session(&user, &session_id)?;
+    Ok(HttpResponse::Ok().json(true))
+}
+
+#[post("/calendar-token", wrap = "from_fn(require_session)")]
+async fn new_calendar_token(user: ReqData<User>) -> Result<impl Responder, AuthServiceError> {
+    let token = create_calendar_token(&user)?;
+    let feed_path = format!("/calendar/{token}.ics");
+    Ok(HttpResponse::Ok().json(CalendarToken { token, feed_path }))
+}
+
+#[delete("/calendar-token", wrap = "from_fn(require_session)")]
+async fn delete_calendar_token(user: ReqData<User>) -> Result<impl Responder, AuthServiceError> {
+    revoke_calendar_token(&user)?;
+    Ok(HttpResponse::Ok().json(true))
 }
 
 pub fn auth_scope() -> Scope {
-    scope("/auth").service(login_user).service(register_user)
+    scope("/auth")
+        .service(login_user)
+        .service(register_user)
+        // session checks sit on the routes themselves so unknown paths still 404
+        .service(logout_user)
+        .service(list_sessions)
+        .service(delete_session)
+        .service(new_calendar_token)
+        .service(delete_calendar_token)
 }
@@ -0,0 +1,25 @@
+use crate::data_models::auth_models::Permission;
+use crate::data_models::calendar::render_calendar;
+use crate::database_services::authentication_services::{authorize, validate_calendar_token};
+use crate::database_services::calendar_database::CalendarDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    get,
+    web::{scope, Path},
+    HttpResponse, Responder, Scope,
+};
+
+#[get("/{token}.ics")]
+async fn get_calendar_feed(path: Path<(String,)>) -> Result<impl Responder, ServiceError> {
+    let token = path.into_inner().0;
+    let user = validate_calendar_token(&token)?;
+    authorize(&user, Permission::View)?;
+    let events = CalendarDataBase::get_events(&user).await?;
+    return Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(render_calendar(&events)));
+}
+
+pub fn calendar_scope() -> Scope {
+    scope("/calendar").service(get_calendar_feed)
+}
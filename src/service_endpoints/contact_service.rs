@@ -0,0 +1,92 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::contacts::{Contact, LendingHistory};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::contact_database::ContactDataBase;
+use crate::database_services::loan_database::LoanDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post, put,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[get("")]
+async fn get_contacts(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let contacts = ContactDataBase::get_contacts(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(contacts));
+}
+
+#[post("")]
+async fn add_contact(
+    user: ReqData<User>,
+    body: Json<Contact>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let contact = ContactDataBase::add_contact(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(contact));
+}
+
+#[get("/{id}")]
+async fn get_contact(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let contact_id = path.into_inner().0;
+    let contact = ContactDataBase::get_contact(&user.household_id, &contact_id).await?;
+    return Ok(HttpResponse::Ok().json(contact));
+}
+
+#[get("/{id}/loans")]
+async fn get_lending_history(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let contact_id = path.into_inner().0;
+    let contact = ContactDataBase::get_contact(&user.household_id, &contact_id).await?;
+    let (holding, returned) = LoanDataBase::get_loans_for_contact(&user.household_id, &contact_id)
+        .await?
+        .into_iter()
+        .partition(|loan| loan.returned_on.is_none());
+    return Ok(HttpResponse::Ok().json(LendingHistory {
+        contact,
+        holding,
+        returned,
+    }));
+}
+
+#[put("/{id}")]
+async fn update_contact(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<Contact>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let contact_id = path.into_inner().0;
+    let was_updated =
+        ContactDataBase::update_contact(&user.household_id, &contact_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(was_updated));
+}
+
+#[delete("/{id}")]
+async fn delete_contact(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let contact_id = path.into_inner().0;
+    let was_deleted = ContactDataBase::delete_contact(&user.household_id, &contact_id).await?;
+    return Ok(HttpResponse::Ok().json(was_deleted));
+}
+
+pub fn contact_scope() -> Scope {
+    scope("/contacts")
+        .service(get_contacts)
+        .service(add_contact)
+        .service(get_lending_history)
+        .service(get_contact)
+        .service(update_contact)
+        .service(delete_contact)
+}
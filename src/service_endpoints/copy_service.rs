@@ -0,0 +1,85 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::copies::{CopyDetails, NewCopy};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::copy_database::CopyDataBase;
+use crate::database_services::hardware_database::HardwareDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post, put,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[post("")]
+async fn add_copy(
+    user: ReqData<User>,
+    body: Json<NewCopy>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let mut copy = CopyDataBase::add_copy(&user.household_id, body.into_inner()).await?;
+    HardwareDataBase::flag_unplayable(&user.household_id, &mut copy).await?;
+    return Ok(HttpResponse::Ok().json(copy));
+}
+
+#[get("/{id}")]
+async fn get_copy(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let copy_id = path.into_inner().0;
+    let copy = CopyDataBase::get_copy(&user.household_id, &copy_id).await?;
+    return Ok(HttpResponse::Ok().json(copy));
+}
+
+#[get("/{id}/compatibility")]
+async fn get_copy_compatibility(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let copy_id = path.into_inner().0;
+    let copy = CopyDataBase::get_copy(&user.household_id, &copy_id).await?;
+    let report = HardwareDataBase::check_copy(&user.household_id, &copy).await?;
+    return Ok(HttpResponse::Ok().json(report));
+}
+
+#[put("/{id}")]
+async fn update_copy(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<CopyDetails>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let copy_id = path.into_inner().0;
+    let before = CopyDataBase::get_copy(&user.household_id, &copy_id).await?;
+    CopyDataBase::update_copy(&user.household_id, &copy_id, body.into_inner()).await?;
+    let mut copy = CopyDataBase::get_copy(&user.household_id, &copy_id).await?;
+    // only a change of region or standard can make the copy unplayable
+    if copy.details.region_code != before.details.region_code
+        || copy.details.video_standard != before.details.video_standard
+    {
+        HardwareDataBase::flag_unplayable(&user.household_id, &mut copy).await?;
+    }
+    return Ok(HttpResponse::Ok().json(copy));
+}
+
+#[delete("/{id}")]
+async fn delete_copy(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let copy_id = path.into_inner().0;
+    let was_deleted = CopyDataBase::delete_copy(&user.household_id, &copy_id).await?;
+    return Ok(HttpResponse::Ok().json(was_deleted));
+}
+
+pub fn copy_scope() -> Scope {
+    scope("/copies")
+        .service(add_copy)
+        .service(get_copy_compatibility)
+        .service(get_copy)
+        .service(update_copy)
+        .service(delete_copy)
+}
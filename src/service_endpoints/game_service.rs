@@ -1,24 +1,96 @@
-use crate::data_models::game::Game;
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::copies::{
+    Completeness, CopyFilter, IncompleteCopy, ItemDetails, NewCopy, NewTitle,
+};
+use crate::data_models::game::{Game, PlayerFilter};
+use crate::data_models::hardware::{HardwareGapReport, PlayableGame, PlayableQuery};
+use crate::data_models::lookup::{CreateOptions, LookupCandidate, MediaKind, MergeRequest};
+use crate::data_models::parental_controls::AgeFilter;
+use crate::data_models::plans::{GamePlan, GamePlanQuery};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::copy_database::{CopyDataBase, CopyMatches};
 use crate::database_services::game_database::GameDataBase;
+use crate::database_services::hardware_database::HardwareDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::database_services::wishlist_database::WishlistDataBase;
 use crate::errors::servive_errors::ServiceError;
 use actix_web::{
     delete, get, post, put,
-    web::{scope, Json, Path},
+    web::{scope, Json, Path, Query, ReqData},
     HttpResponse, Responder, Scope,
 };
+use std::collections::HashMap;
 
 #[post("/new")]
-async fn add_game(new_game: Json<Game>) -> Result<impl Responder, ServiceError> {
+async fn add_game(
+    user: ReqData<User>,
+    body: Json<NewTitle<Game>>,
+    options: Query<CreateOptions>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let NewTitle {
+        item: new_game,
+        copy: copy_details,
+    } = body.into_inner();
+    if !new_game.players.is_valid() {
+        return Err(ServiceError::InvalidPlayerCounts);
+    }
+    if !new_game.has_valid_ratings() {
+        return Err(ServiceError::InvalidRating);
+    }
     let real_new_game = Game::new(
         new_game.title.clone(),
         &new_game.platform.string(),
-        &new_game.rating.string(),
-        new_game.number_of_players,
+        new_game.rating.map(|rating| rating.string()).as_deref(),
+        new_game.regional_ratings.clone(),
+        new_game.players,
     );
     if let Some(game) = real_new_game {
-        let did_insert: bool = GameDataBase::insert_game(game).await?;
+        if !options.allow_duplicate {
+            let duplicates = GameDataBase::find_duplicates(&game, &user.household_id).await?;
+            if !duplicates.is_empty() {
+                return Err(ServiceError::DuplicateFound(
+                    duplicates
+                        .into_iter()
+                        .map(|(game, score)| LookupCandidate::from_game(game, score))
+                        .collect(),
+                ));
+            }
+        }
+        let (game_id, title, platform) =
+            (game.id.clone(), game.title.clone(), game.platform.string());
+        let did_insert: bool = GameDataBase::insert_game(game.clone(), &user.household_id).await?;
+        if !did_insert {
+            return Err(ServiceError::FailedToMakeGame);
+        }
+        // every new title starts out with the one copy that was just added
+        let new_copy = NewCopy {
+            media_kind: MediaKind::Game,
+            media_id: game_id.clone(),
+            details: copy_details,
+        };
+        let mut copy = match CopyDataBase::add_copy(&user.household_id, new_copy).await {
+            Ok(copy) => copy,
+            Err(error) => {
+                // bad copy details shouldn't leave a title behind without any copy
+                GameDataBase::delete_game(Some(game_id), &user.household_id).await?;
+                return Err(error);
+            }
+        };
+        HardwareDataBase::flag_unplayable(&user.household_id, &mut copy).await?;
+        WishlistDataBase::resolve_matching(
+            &user.household_id,
+            MediaKind::Game,
+            &title,
+            &platform,
+            &game_id,
+        )
+        .await?;
         println!("successfully made game");
-        return Ok(HttpResponse::Ok().json(did_insert));
+        return Ok(HttpResponse::Ok().json(ItemDetails {
+            item: game,
+            copies: vec![copy],
+        }));
     } else {
         println!("failed to make game");
         return Err(ServiceError::FailedToMakeGame);
@@ -26,39 +98,212 @@ async fn add_game(new_game: Json<Game>) -> Result<impl Responder, ServiceError>
 }
 
 #[get("/all")]
-async fn get_all_games() -> Result<impl Responder, ServiceError> {
-    let resp = match GameDataBase::get_games().await? {
-        Some(games) => Ok(HttpResponse::Ok().json(games)),
+async fn get_all_games(
+    user: ReqData<User>,
+    filter: Query<CopyFilter>,
+    player_filter: Query<PlayerFilter>,
+    age_filter: Query<AgeFilter>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let matches = CopyMatches::for_filter(&user.household_id, filter.into_inner()).await?;
+    let resp = match GameDataBase::get_games(&user.household_id).await? {
+        Some(mut games) => {
+            games.retain(|game| {
+                controls.allows_game(game)
+                    && matches.allows_game(game)
+                    && player_filter.matches(game)
+                    && age_filter.allows_game(game)
+            });
+            Ok(HttpResponse::Ok().json(games))
+        }
         None => Err(ServiceError::GameNotFound),
     };
     return resp;
 }
 
+#[get("/retro/incomplete")]
+async fn get_incomplete_retro_games(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let games: HashMap<String, Game> = GameDataBase::get_games(&user.household_id)
+        .await?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|game| game.platform.is_retro() && controls.allows_game(game))
+        .map(|game| (game.id.clone(), game))
+        .collect();
+    let mut report: Vec<IncompleteCopy> = CopyDataBase::get_household_copies(&user.household_id)
+        .await?
+        .into_iter()
+        .filter(|copy| {
+            matches!(
+                copy.completeness,
+                Some(Completeness::Loose) | Some(Completeness::Partial)
+            )
+        })
+        .filter_map(|copy| {
+            let game = games.get(&copy.media_id)?.clone();
+            let missing = copy.details.parts.as_ref()?.missing();
+            Some(IncompleteCopy {
+                game,
+                copy,
+                missing,
+            })
+        })
+        .collect();
+    report.sort_by(|left, right| left.game.title.cmp(&right.game.title));
+    return Ok(HttpResponse::Ok().json(report));
+}
+
+#[get("/hardware-gaps")]
+async fn get_hardware_gaps(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let inventory = HardwareDataBase::get_inventory(&user.household_id).await?;
+    let mut report: Vec<HardwareGapReport> = GameDataBase::get_games(&user.household_id)
+        .await?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|game| controls.allows_game(game))
+        .filter_map(|game| {
+            let gaps = inventory.gaps_for(&game);
+            if gaps.is_empty() {
+                return None;
+            }
+            return Some(HardwareGapReport { game, gaps });
+        })
+        .collect();
+    report.sort_by(|left, right| left.game.title.cmp(&right.game.title));
+    return Ok(HttpResponse::Ok().json(report));
+}
+
+#[get("/playable")]
+async fn get_playable_games(
+    user: ReqData<User>,
+    query: Query<PlayableQuery>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let (platform, consoles) =
+        HardwareDataBase::get_consoles_for(&user.household_id, &query).await?;
+    let mut playable: Vec<PlayableGame> = GameDataBase::get_games(&user.household_id)
+        .await?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|game| {
+            controls.allows_game(game)
+                && if consoles.is_empty() {
+                    platform.can_ever_run(game.platform)
+                } else {
+                    consoles
+                        .iter()
+                        .any(|console| console.runs_platform(game.platform))
+                }
+        })
+        .map(|game| PlayableGame {
+            backward_compatible: game.platform != platform,
+            game,
+        })
+        .collect();
+    playable.sort_by(|left, right| {
+        left.backward_compatible
+            .cmp(&right.backward_compatible)
+            .then_with(|| left.game.title.cmp(&right.game.title))
+    });
+    return Ok(HttpResponse::Ok().json(playable));
+}
+
+#[get("/plan")]
+async fn plan_game_night(
+    user: ReqData<User>,
+    query: Query<GamePlanQuery>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    if query.players == 0 {
+        return Err(ServiceError::InvalidPlanQuery);
+    }
+    let controls = ParentalControls::for_user(&user).await?;
+    let inventory = HardwareDataBase::get_inventory(&user.household_id).await?;
+    let mut games = GameDataBase::get_games(&user.household_id)
+        .await?
+        .unwrap_or_default();
+    games.retain(|game| controls.allows_game(game));
+    return Ok(HttpResponse::Ok().json(GamePlan::build(games, &inventory, &query)));
+}
+
+#[get("/duplicates")]
+async fn get_duplicate_games(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let mut clusters = GameDataBase::get_duplicate_clusters(&user.household_id).await?;
+    // a cluster only means something while at least two of its titles are visible
+    for cluster in clusters.iter_mut() {
+        cluster.retain(|game| controls.allows_game(game));
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    return Ok(HttpResponse::Ok().json(clusters));
+}
+
+#[post("/merge")]
+async fn merge_games(
+    user: ReqData<User>,
+    request: Json<MergeRequest>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let merged_count = GameDataBase::merge_games(request.into_inner(), &user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(merged_count));
+}
+
 #[get("/{id}")]
-async fn get_games(path: Path<(String,)>) -> Result<impl Responder, ServiceError> {
+async fn get_games(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
     let id = path.into_inner().0;
-    return match GameDataBase::get_game_with_id(id).await? {
-        Some(game) => Ok(HttpResponse::Ok().json(game)),
-        None => Err(ServiceError::GameNotFound),
+    let controls = ParentalControls::for_user(&user).await?;
+    return match GameDataBase::get_game_with_id(id, &user.household_id).await? {
+        Some(game)
+            if controls.allows_game(&game)
+                || controls
+                    .use_one_time_exception(&user, MediaKind::Game, &game.id)
+                    .await? =>
+        {
+            let copies =
+                CopyDataBase::get_copies(&user.household_id, MediaKind::Game, &game.id).await?;
+            Ok(HttpResponse::Ok().json(ItemDetails { item: game, copies }))
+        }
+        _ => Err(ServiceError::GameNotFound),
     };
 }
 
 #[put("/update")]
-async fn update_game_with(updated_game: Json<Game>) -> Result<impl Responder, ServiceError> {
-    let was_updated: bool = GameDataBase::update_game(updated_game.into_inner()).await?;
+async fn update_game_with(
+    user: ReqData<User>,
+    updated_game: Json<Game>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let was_updated: bool =
+        GameDataBase::update_game(updated_game.into_inner(), &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_updated));
 }
 
 #[delete("/remove/{id}")]
-async fn delete_game_with(path: Path<(String,)>) -> Result<impl Responder, ServiceError> {
+async fn delete_game_with(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
     let id = path.into_inner().0;
-    let was_deleted: bool = GameDataBase::delete_game(Some(id)).await?;
+    let was_deleted: bool = GameDataBase::delete_game(Some(id), &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_deleted));
 }
 
 #[delete("/remove/all")]
-async fn delete_all_games() -> Result<impl Responder, ServiceError> {
-    let was_deleted: bool = GameDataBase::delete_game(None).await?;
+async fn delete_all_games(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::DeleteAll)?;
+    let was_deleted: bool = GameDataBase::delete_game(None, &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_deleted));
 }
 
@@ -66,8 +311,14 @@ pub fn game_scope() -> Scope {
     scope("/game")
         .service(add_game)
         .service(get_all_games)
+        .service(get_incomplete_retro_games)
+        .service(get_hardware_gaps)
+        .service(get_playable_games)
+        .service(plan_game_night)
+        .service(get_duplicate_games)
+        .service(merge_games)
         .service(get_games)
         .service(update_game_with)
-        .service(delete_game_with)
         .service(delete_all_games)
+        .service(delete_game_with)
 }
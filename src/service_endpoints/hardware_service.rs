@@ -0,0 +1,60 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::hardware::Hardware;
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::hardware_database::HardwareDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post, put,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[get("")]
+async fn get_hardware(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let hardware = HardwareDataBase::get_hardware(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(hardware));
+}
+
+#[post("")]
+async fn add_hardware(
+    user: ReqData<User>,
+    body: Json<Hardware>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let hardware = HardwareDataBase::add_hardware(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(hardware));
+}
+
+#[put("/{id}")]
+async fn update_hardware(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<Hardware>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let hardware_id = path.into_inner().0;
+    let was_updated =
+        HardwareDataBase::update_hardware(&user.household_id, &hardware_id, body.into_inner())
+            .await?;
+    return Ok(HttpResponse::Ok().json(was_updated));
+}
+
+#[delete("/{id}")]
+async fn delete_hardware(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let hardware_id = path.into_inner().0;
+    let was_deleted = HardwareDataBase::delete_hardware(&user.household_id, &hardware_id).await?;
+    return Ok(HttpResponse::Ok().json(was_deleted));
+}
+
+pub fn hardware_scope() -> Scope {
+    scope("/hardware")
+        .service(get_hardware)
+        .service(add_hardware)
+        .service(update_hardware)
+        .service(delete_hardware)
+}
@@ -0,0 +1,104 @@
+use crate::data_models::auth_models::{JoinRequest, Permission, RoleChange, User};
+use crate::data_models::parental_controls::{RatingExceptionRequest, RatingLimits};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::household_database::HouseholdDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post, put,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[get("/members")]
+async fn get_members(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let members = HouseholdDataBase::get_members(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(members));
+}
+
+#[put("/members/{user_id}/role")]
+async fn change_member_role(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<RoleChange>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let member_id = path.into_inner().0;
+    let was_updated =
+        HouseholdDataBase::set_member_role(&user.household_id, &member_id, body.role).await?;
+    return Ok(HttpResponse::Ok().json(was_updated));
+}
+
+#[put("/members/{user_id}/limits")]
+async fn change_member_limits(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<RatingLimits>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let member_id = path.into_inner().0;
+    let was_updated =
+        ParentalControls::set_limits(&user.household_id, &member_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(was_updated));
+}
+
+#[get("/exceptions")]
+async fn get_exceptions(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let exceptions = ParentalControls::get_exceptions(&user.household_id, None).await?;
+    return Ok(HttpResponse::Ok().json(exceptions));
+}
+
+#[post("/exceptions")]
+async fn add_exception(
+    user: ReqData<User>,
+    body: Json<RatingExceptionRequest>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let exception = ParentalControls::add_exception(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(exception));
+}
+
+#[delete("/exceptions/{id}")]
+async fn remove_exception(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let id = path.into_inner().0;
+    let was_removed = ParentalControls::remove_exception(&user.household_id, &id).await?;
+    return Ok(HttpResponse::Ok().json(was_removed));
+}
+
+#[post("/invites")]
+async fn create_invite(
+    user: ReqData<User>,
+    body: Json<RoleChange>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ManageMembers)?;
+    let invite = HouseholdDataBase::create_invite(&user.household_id, body.role).await?;
+    return Ok(HttpResponse::Ok().json(invite));
+}
+
+#[post("/join")]
+async fn join_household(
+    user: ReqData<User>,
+    body: Json<JoinRequest>,
+) -> Result<impl Responder, ServiceError> {
+    let did_join =
+        HouseholdDataBase::join_household(&user.user_id, &user.household_id, &body.code).await?;
+    return Ok(HttpResponse::Ok().json(did_join));
+}
+
+pub fn household_scope() -> Scope {
+    scope("/household")
+        .service(get_members)
+        .service(change_member_role)
+        .service(change_member_limits)
+        .service(get_exceptions)
+        .service(add_exception)
+        .service(remove_exception)
+        .service(create_invite)
+        .service(join_household)
+}
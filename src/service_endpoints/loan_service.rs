@@ -0,0 +1,55 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::loans::{CheckinRequest, CheckoutRequest};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::loan_database::LoanDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    get, post,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[post("/checkout")]
+async fn check_out(
+    user: ReqData<User>,
+    body: Json<CheckoutRequest>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let loan = LoanDataBase::check_out(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(loan));
+}
+
+#[post("/{id}/checkin")]
+async fn check_in(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Option<Json<CheckinRequest>>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let loan_id = path.into_inner().0;
+    let request = body.map(|body| body.into_inner()).unwrap_or_default();
+    let was_returned = LoanDataBase::check_in(&user.household_id, &loan_id, request).await?;
+    return Ok(HttpResponse::Ok().json(was_returned));
+}
+
+#[get("")]
+async fn get_active_loans(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let loans = LoanDataBase::get_active_loans(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(loans));
+}
+
+#[get("/overdue")]
+async fn get_overdue_loans(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let loans = LoanDataBase::get_overdue_loans(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(loans));
+}
+
+pub fn loan_scope() -> Scope {
+    scope("/loans")
+        .service(check_out)
+        .service(check_in)
+        .service(get_active_loans)
+        .service(get_overdue_loans)
+}
@@ -0,0 +1,113 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::locations::{LocationAssignment, LocationContents, NewLocation};
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::game_database::GameDataBase;
+use crate::database_services::location_database::{LocationDataBase, LocationTree};
+use crate::database_services::movie_database::MovieDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post, put,
+    web::{scope, Json, Path, ReqData},
+    HttpResponse, Responder, Scope,
+};
+use std::collections::HashSet;
+
+#[get("")]
+async fn get_locations(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let tree = LocationTree::for_household(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(tree.all()));
+}
+
+#[post("")]
+async fn add_location(
+    user: ReqData<User>,
+    body: Json<NewLocation>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let location = LocationDataBase::add_location(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(location));
+}
+
+#[put("/assign")]
+async fn assign_location(
+    user: ReqData<User>,
+    body: Json<LocationAssignment>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let was_assigned =
+        LocationDataBase::assign_location(&user.household_id, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(was_assigned));
+}
+
+#[get("/{id}/contents")]
+async fn get_location_contents(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let location_id = path.into_inner().0;
+    let controls = ParentalControls::for_user(&user).await?;
+    let mut visible: HashSet<(MediaKind, String)> = HashSet::new();
+    for game in GameDataBase::get_games(&user.household_id)
+        .await?
+        .unwrap_or_default()
+    {
+        if controls.allows_game(&game) {
+            visible.insert((MediaKind::Game, game.id));
+        }
+    }
+    for movie in MovieDataBase::get_all_movies(&user.household_id)
+        .await?
+        .unwrap_or_default()
+    {
+        if controls.allows_movie(&movie) {
+            visible.insert((MediaKind::Movie, movie.id));
+        }
+    }
+    let mut items = LocationDataBase::get_contents(&user.household_id, &location_id).await?;
+    items.retain(|item| visible.contains(&(item.kind, item.id.clone())));
+    let location = LocationTree::for_household(&user.household_id)
+        .await?
+        .get(&location_id)
+        .cloned()
+        .ok_or(ServiceError::LocationNotFound)?;
+    return Ok(HttpResponse::Ok().json(LocationContents { location, items }));
+}
+
+#[put("/{id}")]
+async fn update_location(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<NewLocation>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let location_id = path.into_inner().0;
+    let was_updated =
+        LocationDataBase::update_location(&user.household_id, &location_id, body.into_inner())
+            .await?;
+    return Ok(HttpResponse::Ok().json(was_updated));
+}
+
+#[delete("/{id}")]
+async fn delete_location(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let location_id = path.into_inner().0;
+    let was_deleted = LocationDataBase::delete_location(&user.household_id, &location_id).await?;
+    return Ok(HttpResponse::Ok().json(was_deleted));
+}
+
+pub fn location_scope() -> Scope {
+    scope("/locations")
+        .service(get_locations)
+        .service(add_location)
+        .service(assign_location)
+        .service(get_location_contents)
+        .service(update_location)
+        .service(delete_location)
+}
@@ -0,0 +1,82 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::copies::CopyFilter;
+use crate::data_models::lookup::{LookupCandidate, LookupQuery, LookupResponse};
+use crate::data_models::parental_controls::AgeFilter;
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::copy_database::CopyMatches;
+use crate::database_services::game_database::GameDataBase;
+use crate::database_services::location_database::{LocationDataBase, LocationTree};
+use crate::database_services::movie_database::MovieDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::database_services::title_matching::LIKELY_SAME_THRESHOLD;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    get,
+    web::{scope, Query, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[get("")]
+async fn lookup_title(
+    user: ReqData<User>,
+    query: Query<LookupQuery>,
+    filter: Query<CopyFilter>,
+    age_filter: Query<AgeFilter>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let query = query.into_inner();
+    if query.q.trim().is_empty() {
+        return Err(ServiceError::MissingSearchTerm);
+    }
+    let controls = ParentalControls::for_user(&user).await?;
+    let matches = CopyMatches::for_filter(&user.household_id, filter.into_inner()).await?;
+    let games = GameDataBase::search_games(&query.q, &user.household_id).await?;
+    let movies = MovieDataBase::search_movies(&query.q, &user.household_id).await?;
+    let mut candidates: Vec<LookupCandidate> = games
+        .into_iter()
+        .filter(|(game, _)| {
+            controls.allows_game(game) && matches.allows_game(game) && age_filter.allows_game(game)
+        })
+        .map(|(game, score)| LookupCandidate::from_game(game, score))
+        .chain(
+            movies
+                .into_iter()
+                .filter(|(movie, _)| {
+                    controls.allows_movie(movie)
+                        && matches.allows_movie(movie)
+                        && age_filter.allows_movie(movie)
+                })
+                .map(|(movie, score)| LookupCandidate::from_movie(movie, score)),
+        )
+        .collect();
+    candidates.sort_by(|left, right| right.score.total_cmp(&left.score));
+    candidates.truncate(query.limit.unwrap_or(DEFAULT_LIMIT));
+    let tree = LocationTree::for_household(&user.household_id).await?;
+    let item_locations = LocationDataBase::get_item_locations(&user.household_id).await?;
+    for candidate in candidates.iter_mut() {
+        candidate.locations = item_locations
+            .get(&(candidate.kind, candidate.id.clone()))
+            .map(|location_ids| {
+                location_ids
+                    .iter()
+                    .filter_map(|location_id| tree.path(location_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+    let likely_owned = candidates
+        .first()
+        .map(|candidate| candidate.score >= LIKELY_SAME_THRESHOLD)
+        .unwrap_or(false);
+    return Ok(HttpResponse::Ok().json(LookupResponse {
+        query: query.q,
+        likely_owned,
+        candidates,
+    }));
+}
+
+pub fn lookup_scope() -> Scope {
+    scope("/lookup").service(lookup_title)
+}
@@ -1,59 +1,237 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::copies::{ItemDetails, NewCopy, NewTitle};
+use crate::data_models::lookup::{CreateOptions, LookupCandidate, MediaKind, MergeRequest};
 use crate::data_models::movie::Movie;
+use crate::data_models::plans::{MoviePlan, MoviePlanQuery};
+use crate::data_models::watch_log::WatchRequest;
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::copy_database::CopyDataBase;
+use crate::database_services::hardware_database::HardwareDataBase;
 use crate::database_services::movie_database::MovieDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::database_services::watch_log_database::WatchLogDataBase;
+use crate::database_services::wishlist_database::WishlistDataBase;
 use crate::errors::servive_errors::ServiceError;
 use actix_web::{
     delete, get, post, put,
-    web::{scope, Json, Path},
+    web::{scope, Json, Path, Query, ReqData},
     HttpResponse, Responder, Scope,
 };
 
 #[post("/new")]
-async fn add_movie(new_movie: Json<Movie>) -> Result<impl Responder, ServiceError> {
+async fn add_movie(
+    user: ReqData<User>,
+    body: Json<NewTitle<Movie>>,
+    options: Query<CreateOptions>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Create)?;
+    let NewTitle {
+        item: new_movie,
+        copy: copy_details,
+    } = body.into_inner();
     if let Some(movie) = Movie::new(
         &new_movie.title,
         &new_movie.format.string(),
         &new_movie.rating.string(),
+        new_movie.runtime_minutes,
     ) {
-        let did_insert: bool = MovieDataBase::new_movie_with(movie).await?;
-        return Ok(HttpResponse::Ok().json(did_insert));
+        if !options.allow_duplicate {
+            let duplicates = MovieDataBase::find_duplicates(&movie, &user.household_id).await?;
+            if !duplicates.is_empty() {
+                return Err(ServiceError::DuplicateFound(
+                    duplicates
+                        .into_iter()
+                        .map(|(movie, score)| LookupCandidate::from_movie(movie, score))
+                        .collect(),
+                ));
+            }
+        }
+        let (movie_id, title, format) =
+            (movie.id.clone(), movie.title.clone(), movie.format.string());
+        let did_insert: bool =
+            MovieDataBase::new_movie_with(movie.clone(), &user.household_id).await?;
+        if !did_insert {
+            return Err(ServiceError::FailedToMakeMovie);
+        }
+        // every new title starts out with the one copy that was just added
+        let new_copy = NewCopy {
+            media_kind: MediaKind::Movie,
+            media_id: movie_id.clone(),
+            details: copy_details,
+        };
+        let mut copy = match CopyDataBase::add_copy(&user.household_id, new_copy).await {
+            Ok(copy) => copy,
+            Err(error) => {
+                // bad copy details shouldn't leave a title behind without any copy
+                MovieDataBase::delete_movie(Some(movie_id), &user.household_id).await?;
+                return Err(error);
+            }
+        };
+        HardwareDataBase::flag_unplayable(&user.household_id, &mut copy).await?;
+        WishlistDataBase::resolve_matching(
+            &user.household_id,
+            MediaKind::Movie,
+            &title,
+            &format,
+            &movie_id,
+        )
+        .await?;
+        return Ok(HttpResponse::Ok().json(ItemDetails {
+            item: movie,
+            copies: vec![copy],
+        }));
     } else {
         return Err(ServiceError::ConnectionFailure);
     }
 }
 
 #[get("/all")]
-async fn get_all_movies() -> Result<impl Responder, ServiceError> {
-    return match MovieDataBase::get_all_movies().await? {
-        Some(movies) => Ok(HttpResponse::Ok().json(movies)),
+async fn get_all_movies(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    return match MovieDataBase::get_all_movies(&user.household_id).await? {
+        Some(mut movies) => {
+            movies.retain(|movie| controls.allows_movie(movie));
+            Ok(HttpResponse::Ok().json(movies))
+        }
         None => Err(ServiceError::MovieNotFound),
     };
 }
 
+#[get("/duplicates")]
+async fn get_duplicate_movies(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let mut clusters = MovieDataBase::get_duplicate_clusters(&user.household_id).await?;
+    // a cluster only means something while at least two of its titles are visible
+    for cluster in clusters.iter_mut() {
+        cluster.retain(|movie| controls.allows_movie(movie));
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    return Ok(HttpResponse::Ok().json(clusters));
+}
+
+#[post("/merge")]
+async fn merge_movies(
+    user: ReqData<User>,
+    request: Json<MergeRequest>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
+    let merged_count =
+        MovieDataBase::merge_movies(request.into_inner(), &user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(merged_count));
+}
+
+#[get("/plan")]
+async fn plan_movie_night(
+    user: ReqData<User>,
+    query: Query<MoviePlanQuery>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let controls = ParentalControls::for_user(&user).await?;
+    let group_ceiling =
+        ParentalControls::strictest_movie_limit(&user.household_id, &query.attendee_names())
+            .await?;
+    let last_watched = WatchLogDataBase::last_watched(&user.household_id).await?;
+    let mut movies = MovieDataBase::get_all_movies(&user.household_id)
+        .await?
+        .unwrap_or_default();
+    movies.retain(|movie| controls.allows_movie(movie));
+    return Ok(HttpResponse::Ok().json(MoviePlan::build(
+        movies,
+        &last_watched,
+        group_ceiling,
+        &query,
+    )));
+}
+
+#[post("/{id}/watched")]
+async fn log_watch(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Option<Json<WatchRequest>>,
+) -> Result<impl Responder, ServiceError> {
+    // anyone can log what they watched, as long as they're allowed to see the movie
+    authorize(&user, Permission::View)?;
+    let movie_id = path.into_inner().0;
+    let controls = ParentalControls::for_user(&user).await?;
+    match MovieDataBase::get_movie_with_id(movie_id.clone(), &user.household_id).await? {
+        Some(movie) if controls.allows_movie(&movie) => (),
+        _ => return Err(ServiceError::MovieNotFound),
+    }
+    let request = body.map(|body| body.into_inner()).unwrap_or_default();
+    let entry = WatchLogDataBase::log_watch(&user, &movie_id, request).await?;
+    return Ok(HttpResponse::Ok().json(entry));
+}
+
+#[get("/{id}/watched")]
+async fn get_watch_history(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let movie_id = path.into_inner().0;
+    let controls = ParentalControls::for_user(&user).await?;
+    match MovieDataBase::get_movie_with_id(movie_id.clone(), &user.household_id).await? {
+        Some(movie) if controls.allows_movie(&movie) => (),
+        _ => return Err(ServiceError::MovieNotFound),
+    }
+    let history = WatchLogDataBase::get_history(&user.household_id, &movie_id).await?;
+    return Ok(HttpResponse::Ok().json(history));
+}
+
 #[get("/{id}")]
-async fn get_movie(path: Path<(String,)>) -> Result<impl Responder, ServiceError> {
+async fn get_movie(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
     let id = path.into_inner().0;
-    return match MovieDataBase::get_movie_with_id(id).await? {
-        Some(movie) => Ok(HttpResponse::Ok().json(movie)),
-        None => Err(ServiceError::MovieNotFound),
+    let controls = ParentalControls::for_user(&user).await?;
+    return match MovieDataBase::get_movie_with_id(id, &user.household_id).await? {
+        Some(movie)
+            if controls.allows_movie(&movie)
+                || controls
+                    .use_one_time_exception(&user, MediaKind::Movie, &movie.id)
+                    .await? =>
+        {
+            let copies =
+                CopyDataBase::get_copies(&user.household_id, MediaKind::Movie, &movie.id).await?;
+            Ok(HttpResponse::Ok().json(ItemDetails {
+                item: movie,
+                copies,
+            }))
+        }
+        _ => Err(ServiceError::MovieNotFound),
     };
 }
 
 #[put("/update")]
-async fn update_movie_with(updated_movie: Json<Movie>) -> Result<impl Responder, ServiceError> {
-    let was_updated: bool = MovieDataBase::update_movie_with(updated_movie.into_inner()).await?;
+async fn update_movie_with(
+    user: ReqData<User>,
+    updated_movie: Json<Movie>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Update)?;
+    let was_updated: bool =
+        MovieDataBase::update_movie_with(updated_movie.into_inner(), &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_updated));
 }
 
 #[delete("/remove/{id}")]
-async fn delete_movie_with(path: Path<(String,)>) -> Result<impl Responder, ServiceError> {
+async fn delete_movie_with(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::Delete)?;
     let id = path.into_inner().0;
-    let was_deleted: bool = MovieDataBase::delete_movie(Some(id)).await?;
+    let was_deleted: bool = MovieDataBase::delete_movie(Some(id), &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_deleted));
 }
 
 #[delete("/remove/all")]
-async fn delete_all_movies() -> Result<impl Responder, ServiceError> {
-    let was_deleted: bool = MovieDataBase::delete_movie(None).await?;
+async fn delete_all_movies(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::DeleteAll)?;
+    let was_deleted: bool = MovieDataBase::delete_movie(None, &user.household_id).await?;
     return Ok(HttpResponse::Ok().json(was_deleted));
 }
 
@@ -61,6 +239,11 @@ pub fn movie_scope() -> Scope {
     scope("/movie")
         .service(add_movie)
         .service(get_all_movies)
+        .service(get_duplicate_movies)
+        .service(merge_movies)
+        .service(plan_movie_night)
+        .service(log_watch)
+        .service(get_watch_history)
         .service(get_movie)
         .service(update_movie_with)
         .service(delete_all_movies)
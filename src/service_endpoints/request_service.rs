@@ -0,0 +1,117 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::content_requests::{Decision, NewContentRequest, RequestFilter};
+use crate::data_models::lookup::MediaKind;
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::content_request_database::ContentRequestDataBase;
+use crate::database_services::game_database::GameDataBase;
+use crate::database_services::movie_database::MovieDataBase;
+use crate::database_services::parental_controls::ParentalControls;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    get, post,
+    web::{scope, Json, Path, Query, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[post("")]
+async fn create_request(
+    user: ReqData<User>,
+    body: Json<NewContentRequest>,
+) -> Result<impl Responder, ServiceError> {
+    let request = body.into_inner();
+    let controls = ParentalControls::for_user(&user).await?;
+    let already_allowed = match request.media_kind {
+        MediaKind::Game => {
+            let game = GameDataBase::get_game_with_id(request.media_id.clone(), &user.household_id)
+                .await?
+                .ok_or(ServiceError::GameNotFound)?;
+            controls.allows_game(&game)
+        }
+        MediaKind::Movie => {
+            let movie =
+                MovieDataBase::get_movie_with_id(request.media_id.clone(), &user.household_id)
+                    .await?
+                    .ok_or(ServiceError::MovieNotFound)?;
+            controls.allows_movie(&movie)
+        }
+    };
+    if already_allowed || controls.has_one_time_exception(request.media_kind, &request.media_id) {
+        return Err(ServiceError::RequestNotNeeded);
+    }
+    let created =
+        ContentRequestDataBase::create_request(&user.household_id, &user.user_id, request).await?;
+    return Ok(HttpResponse::Ok().json(created));
+}
+
+#[get("/mine")]
+async fn get_my_requests(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    let requests =
+        ContentRequestDataBase::get_requests(&user.household_id, Some(&user.user_id), None).await?;
+    return Ok(HttpResponse::Ok().json(requests));
+}
+
+#[get("")]
+async fn get_requests(
+    user: ReqData<User>,
+    filter: Query<RequestFilter>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ApproveRequests)?;
+    let requests =
+        ContentRequestDataBase::get_requests(&user.household_id, None, filter.status).await?;
+    return Ok(HttpResponse::Ok().json(requests));
+}
+
+#[get("/decisions")]
+async fn get_decisions(user: ReqData<User>) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ApproveRequests)?;
+    let decisions = ContentRequestDataBase::get_decisions(&user.household_id).await?;
+    return Ok(HttpResponse::Ok().json(decisions));
+}
+
+#[post("/{id}/approve")]
+async fn approve_request(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<Decision>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ApproveRequests)?;
+    let request_id = path.into_inner().0;
+    let decision = ContentRequestDataBase::decide_request(
+        &user.household_id,
+        &request_id,
+        &user.user_id,
+        true,
+        body.into_inner(),
+    )
+    .await?;
+    return Ok(HttpResponse::Ok().json(decision));
+}
+
+#[post("/{id}/deny")]
+async fn deny_request(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+    body: Json<Decision>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::ApproveRequests)?;
+    let request_id = path.into_inner().0;
+    let decision = ContentRequestDataBase::decide_request(
+        &user.household_id,
+        &request_id,
+        &user.user_id,
+        false,
+        body.into_inner(),
+    )
+    .await?;
+    return Ok(HttpResponse::Ok().json(decision));
+}
+
+pub fn request_scope() -> Scope {
+    scope("/requests")
+        .service(create_request)
+        .service(get_my_requests)
+        .service(get_decisions)
+        .service(get_requests)
+        .service(approve_request)
+        .service(deny_request)
+}
@@ -0,0 +1,96 @@
+use crate::data_models::auth_models::{Permission, User};
+use crate::data_models::wishlist::{NewWishlistItem, WishlistQuery};
+use crate::database_services::authentication_services::authorize;
+use crate::database_services::wishlist_database::WishlistDataBase;
+use crate::errors::servive_errors::ServiceError;
+use actix_web::{
+    delete, get, post,
+    web::{scope, Json, Path, Query, ReqData},
+    HttpResponse, Responder, Scope,
+};
+
+#[get("")]
+async fn get_household_wishlist(
+    user: ReqData<User>,
+    query: Query<WishlistQuery>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let items: Vec<_> =
+        WishlistDataBase::get_items(&user.household_id, None, query.include_resolved)
+            .await?
+            .into_iter()
+            .map(|item| item.hide_claim_from(&user.user_id))
+            .collect();
+    return Ok(HttpResponse::Ok().json(items));
+}
+
+#[get("/mine")]
+async fn get_my_wishlist(
+    user: ReqData<User>,
+    query: Query<WishlistQuery>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let items: Vec<_> = WishlistDataBase::get_items(
+        &user.household_id,
+        Some(&user.user_id),
+        query.include_resolved,
+    )
+    .await?
+    .into_iter()
+    .map(|item| item.hide_claim_from(&user.user_id))
+    .collect();
+    return Ok(HttpResponse::Ok().json(items));
+}
+
+#[post("")]
+async fn add_wishlist_item(
+    user: ReqData<User>,
+    body: Json<NewWishlistItem>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let item = WishlistDataBase::add_item(&user, body.into_inner()).await?;
+    return Ok(HttpResponse::Ok().json(item.hide_claim_from(&user.user_id)));
+}
+
+#[delete("/{id}")]
+async fn delete_wishlist_item(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let item_id = path.into_inner().0;
+    let was_deleted = WishlistDataBase::delete_item(&user, &item_id).await?;
+    return Ok(HttpResponse::Ok().json(was_deleted));
+}
+
+#[post("/{id}/claim")]
+async fn claim_wishlist_item(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let item_id = path.into_inner().0;
+    let was_claimed = WishlistDataBase::claim_item(&user, &item_id).await?;
+    return Ok(HttpResponse::Ok().json(was_claimed));
+}
+
+#[delete("/{id}/claim")]
+async fn unclaim_wishlist_item(
+    user: ReqData<User>,
+    path: Path<(String,)>,
+) -> Result<impl Responder, ServiceError> {
+    authorize(&user, Permission::View)?;
+    let item_id = path.into_inner().0;
+    let was_released = WishlistDataBase::unclaim_item(&user, &item_id).await?;
+    return Ok(HttpResponse::Ok().json(was_released));
+}
+
+pub fn wishlist_scope() -> Scope {
+    scope("/wishlist")
+        .service(get_household_wishlist)
+        .service(get_my_wishlist)
+        .service(add_wishlist_item)
+        .service(delete_wishlist_item)
+        .service(claim_wishlist_item)
+        .service(unclaim_wishlist_item)
+}